@@ -0,0 +1,124 @@
+// Compares updating the player distance map incrementally as the player walks
+// around against recomputing it from scratch after every step.
+//
+// Run with `cargo bench -p meters`.
+
+extern crate direction;
+extern crate grid_2d;
+extern crate grid_search;
+extern crate meters;
+extern crate rand;
+
+use direction::*;
+use grid_2d::*;
+use grid_search::*;
+use meters::distance_map::IncrementalDistanceMap;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+const NUM_STEPS: usize = 1000;
+const WALL_PROBABILITY: f64 = 0.3;
+
+struct Walls(Grid<bool>);
+
+impl SolidGrid for Walls {
+    fn is_solid(&self, coord: Coord) -> Option<bool> {
+        self.0.get(coord).cloned()
+    }
+}
+
+fn random_walls<R: Rng>(size: Size, rng: &mut R) -> Walls {
+    Walls(Grid::new_fn(size, |_| rng.gen_bool(WALL_PROBABILITY)))
+}
+
+fn random_walk<R: Rng>(walls: &Walls, rng: &mut R) -> Vec<Coord> {
+    let mut coord = walls
+        .0
+        .enumerate()
+        .find(|&(_, &solid)| !solid)
+        .map(|(coord, _)| coord)
+        .expect("No open cells");
+    let mut walk = vec![coord];
+    while walk.len() < NUM_STEPS {
+        let directions = CardinalDirections
+            .into_iter()
+            .filter(|direction| walls.is_solid(coord + direction.coord()) == Some(false))
+            .collect::<Vec<_>>();
+        if let Some(direction) = directions.choose(rng) {
+            coord += direction.coord();
+        }
+        walk.push(coord);
+    }
+    walk
+}
+
+fn full(walls: &Walls, walk: &[Coord]) -> Duration {
+    let mut bfs = BfsContext::new(walls.0.size());
+    let mut distance_map: UniformDistanceMap<u32, _> =
+        UniformDistanceMap::new(walls.0.size(), DirectionsCardinal);
+    let start = Instant::now();
+    for &coord in walk {
+        bfs.populate_uniform_distance_map(walls, coord, Default::default(), &mut distance_map)
+            .unwrap();
+    }
+    start.elapsed()
+}
+
+fn incremental(walls: &Walls, walk: &[Coord]) -> Duration {
+    let mut distance_map = IncrementalDistanceMap::new(walls.0.size());
+    let start = Instant::now();
+    for &coord in walk {
+        distance_map.move_origin(walls, coord);
+    }
+    start.elapsed()
+}
+
+// Checks that the incremental map agrees with a full recomputation at every
+// step of the walk, and after toggling cells next to the walk
+fn check(walls: &mut Walls, walk: &[Coord]) {
+    let mut bfs = BfsContext::new(walls.0.size());
+    let mut expected: UniformDistanceMap<u32, _> =
+        UniformDistanceMap::new(walls.0.size(), DirectionsCardinal);
+    let mut distance_map = IncrementalDistanceMap::new(walls.0.size());
+    for (i, &coord) in walk.iter().enumerate() {
+        distance_map.move_origin(walls, coord);
+        if i % 2 == 1 {
+            let toggle = coord + Coord::new(2, 1);
+            if let Some(solid) = walls.0.get_mut(toggle) {
+                *solid = !*solid;
+            }
+            distance_map.update_cell(walls, toggle);
+        }
+        bfs.populate_uniform_distance_map(walls, coord, Default::default(), &mut expected)
+            .unwrap();
+        for coord in walls.0.coord_iter() {
+            assert_eq!(
+                distance_map.cost(coord),
+                expected.cost(coord),
+                "Incremental distance map differs at {:?}",
+                coord
+            );
+        }
+    }
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for &size in &[Size::new(40, 40), Size::new(80, 80), Size::new(160, 160)] {
+        let mut walls = random_walls(size, &mut rng);
+        let walk = random_walk(&walls, &mut rng);
+        let full = full(&walls, &walk);
+        let incremental = incremental(&walls, &walk);
+        println!(
+            "{}x{}: full {:?}, incremental {:?} ({} steps)",
+            size.x(),
+            size.y(),
+            full,
+            incremental,
+            NUM_STEPS,
+        );
+        check(&mut walls, &walk[0..100]);
+    }
+}
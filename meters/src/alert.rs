@@ -10,5 +10,16 @@ pub enum Alert {
     NoSuchMeter,
     RailgunWhichDirection,
     BlinkWhichDirection,
+    GrenadeWhichDirection,
+    DecoyWhichDirection,
+    NoDecoy,
+    ShieldWhichDirection,
+    NoShield,
+    NoHaste,
+    ShieldBlock,
     BeaconActive,
+    Crushed,
+    DamageResisted,
+    DamageWeakness,
+    Explored,
 }
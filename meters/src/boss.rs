@@ -0,0 +1,105 @@
+use direction::*;
+use entity_store::*;
+use message_queues::PushMessages;
+use npc_info::NpcInfo;
+use prototypes;
+use rand::Rng;
+use scheduler;
+
+pub const QUEEN_BOSS_HEALTH: i32 = 12;
+
+// The boss queen enters a new phase each time her health drops to one of these
+pub const QUEEN_PHASE_BOUNDARIES: &[i32] = &[9, 6, 3];
+
+const QUEEN_NUM_EGGS: usize = 3;
+const QUEEN_NUM_LARVAE: usize = 4;
+
+pub fn queen_phase(health: i32) -> u32 {
+    QUEEN_PHASE_BOUNDARIES
+        .iter()
+        .filter(|&&boundary| health <= boundary)
+        .count() as u32
+}
+
+fn spawn_around<M, F>(
+    coord: Coord,
+    count: usize,
+    spatial_hash: &SpatialHashTable,
+    id_allocator: &mut EntityIdAllocator,
+    messages: &mut M,
+    mut f: F,
+) where
+    M: PushMessages,
+    F: FnMut(EntityId, Coord, &mut M),
+{
+    let free_coords = Directions
+        .into_iter()
+        .map(|direction| coord + direction.coord())
+        .filter(|&coord| {
+            spatial_hash
+                .get(coord)
+                .map(|cell| {
+                    cell.solid_count == 0 && cell.npc_set.is_empty() && cell.player_count == 0
+                })
+                .unwrap_or(false)
+        })
+        .take(count);
+    for coord in free_coords {
+        f(id_allocator.allocate(), coord, messages);
+    }
+}
+
+pub fn enter_queen_phase<M, R>(
+    id: EntityId,
+    phase: u32,
+    entity_store: &EntityStore,
+    spatial_hash: &SpatialHashTable,
+    id_allocator: &mut EntityIdAllocator,
+    messages: &mut M,
+    rng: &mut R,
+) where
+    M: PushMessages,
+    R: Rng,
+{
+    let coord = if let Some(&coord) = entity_store.coord.get(&id) {
+        coord
+    } else {
+        return;
+    };
+    match phase {
+        1 => {
+            spawn_around(
+                coord,
+                QUEEN_NUM_EGGS,
+                spatial_hash,
+                id_allocator,
+                messages,
+                |id, coord, messages| prototypes::egg(id, coord, messages, rng),
+            );
+        }
+        2 => {
+            spawn_around(
+                coord,
+                QUEEN_NUM_LARVAE,
+                spatial_hash,
+                id_allocator,
+                messages,
+                |id, coord, messages| {
+                    prototypes::larvae(id, coord, messages, rng);
+                    messages.change(insert::npc(
+                        id,
+                        NpcInfo {
+                            boss: false,
+                            mobile: true,
+                            active: true,
+                        },
+                    ));
+                },
+            );
+            messages.change(insert::retreat(id));
+        }
+        _ => {
+            messages.change(insert::speed(id, scheduler::FAST_SPEED));
+        }
+    }
+}
@@ -5,16 +5,19 @@ use message_queues::*;
 use event::*;
 use world::World;
 use policy;
+use status::{self, StatusChange, StatusEffectType};
 
 #[derive(Clone, Debug)]
 pub struct ChangeContext {
     ids_to_free: HashSet<EntityId>,
+    status_changes: Vec<StatusChange>,
 }
 
 impl ChangeContext {
     pub fn new() -> Self {
         Self {
             ids_to_free: HashSet::new(),
+            status_changes: Vec::new(),
         }
     }
 
@@ -35,6 +38,11 @@ impl ChangeContext {
                 self.ids_to_free.insert(id);
             }
 
+            // status changes from the previous batch are combined into a
+            // single change per entity
+            ::std::mem::swap(&mut self.status_changes, &mut messages.status_changes);
+            status::commit_changes(&mut self.status_changes, &world.entity_store, messages);
+
             if messages.changes.is_empty() {
                 break;
             }
@@ -57,6 +65,16 @@ impl ChangeContext {
                     continue;
                 }
 
+                match &change {
+                    &EntityChange::Insert(id, ComponentValue::Solid)
+                    | &EntityChange::Remove(id, ComponentType::Solid) => {
+                        if let Some(&coord) = world.entity_store.coord.get(&id) {
+                            messages.solidity_changed.push(coord);
+                        }
+                    }
+                    _ => (),
+                }
+
                 world
                     .spatial_hash
                     .update(&world.entity_store, &change, world.count);
@@ -64,7 +82,9 @@ impl ChangeContext {
                 world.entity_store.commit(change);
             }
 
-            if world.entity_store.push_wave.is_empty() {
+            // pushed entities only move once the consequences of their
+            // previous move (e.g. crushing) have been applied
+            if world.entity_store.push_wave.is_empty() && messages.changes.is_empty() {
                 let mut pushed = world.entity_store.pushed.iter().collect::<Vec<_>>();
                 pushed.sort_by(|a, b| {
                     b.1.distance.cmp(&a.1.distance)
@@ -80,10 +100,7 @@ impl ChangeContext {
                             pushed.range -= 1;
                             messages.change(insert::pushed(*id, pushed));
                         }
-                        if let Some(mut tile) = world.entity_store.tile_info.get(id).cloned() {
-                            tile.pushed = false;
-                            messages.change(insert::tile_info(*id, tile));
-                        }
+                        status::clear(*id, StatusEffectType::Pushed, messages);
                     }
                 }
             }
@@ -2,9 +2,12 @@ use std::time::Duration;
 use grid_2d::Coord;
 use direction::CardinalDirection;
 use animation::*;
-use entity_store::EntityId;
+use entity_store::*;
 use prototypes::Prototype;
+use noise;
 use timing;
+use weapons::{AreaDamage, ExplosionInfo};
+use stats::DamageSource;
 use message_queues::PushMessages;
 
 pub fn punch<M: PushMessages>(
@@ -13,6 +16,7 @@ pub fn punch<M: PushMessages>(
     direction: CardinalDirection,
     messages: &mut M,
 ) {
+    messages.noise(coord, noise::PUNCH_NOISE_RADIUS);
     let punch = Prototype::Punch(id, coord, direction);
     temporary_at_coord(
         coord,
@@ -93,6 +97,90 @@ pub fn bullet<M: PushMessages>(id: EntityId, messages: &mut M) {
     );
 }
 
+pub fn spit<M: PushMessages>(id: EntityId, messages: &mut M) {
+    slide(
+        id,
+        Duration::from_millis(timing::SPIT_SLIDE_MILLIS),
+        messages,
+    );
+}
+
+pub fn grenade<M: PushMessages>(id: EntityId, messages: &mut M) {
+    slide(
+        id,
+        Duration::from_millis(timing::GRENADE_SLIDE_MILLIS),
+        messages,
+    );
+}
+
+pub fn explosion<M: PushMessages>(
+    id: EntityId,
+    coord: Coord,
+    info: ExplosionInfo,
+    messages: &mut M,
+) {
+    let explosion = Prototype::Explosion(id, coord, info);
+    temporary_at_coord(
+        coord,
+        explosion,
+        Duration::from_millis(timing::EXPLOSION_MILLIS),
+        messages,
+    );
+}
+
+fn has_line_of_effect(from: Coord, to: Coord, spatial_hash: &SpatialHashTable) -> bool {
+    let delta = to - from;
+    let steps = ::std::cmp::max(delta.x.abs(), delta.y.abs());
+    (1..steps).all(|i| {
+        let t = i as f64 / steps as f64;
+        let coord = from + Coord::new(
+            (delta.x as f64 * t).round() as i32,
+            (delta.y as f64 * t).round() as i32,
+        );
+        spatial_hash
+            .get(coord)
+            .map(|cell| cell.opacity_total == 0)
+            .unwrap_or(false)
+    })
+}
+
+pub fn area_damage<M: PushMessages>(
+    coord: Coord,
+    area: AreaDamage,
+    source: DamageSource,
+    spatial_hash: &SpatialHashTable,
+    id_allocator: &mut EntityIdAllocator,
+    messages: &mut M,
+) {
+    let radius = area.radius;
+    for y in -radius..=radius {
+        for x in -radius..=radius {
+            if x * x + y * y > radius * radius + radius {
+                continue;
+            }
+            let explosion_coord = coord + Coord::new(x, y);
+            let open = spatial_hash
+                .get(explosion_coord)
+                .map(|cell| cell.solid_count == 0)
+                .unwrap_or(false);
+            if !open {
+                continue;
+            }
+            if area.line_of_effect && !has_line_of_effect(coord, explosion_coord, spatial_hash) {
+                continue;
+            }
+            let distance = ((x * x + y * y) as f64).sqrt().round() as i32;
+            let damage = area.damage - area.falloff * distance;
+            if damage > 0 {
+                let explosion_id = id_allocator.allocate();
+                let info = ExplosionInfo { damage, source };
+                explosion(explosion_id, explosion_coord, info, messages);
+            }
+        }
+    }
+    messages.noise(coord, area.noise_radius);
+}
+
 pub fn damage_flash<M: PushMessages>(id: EntityId, messages: &mut M) {
     start_animation(
         AnimationState::DamageFlash(id, Duration::from_millis(timing::DAMAGE_FLASH_MILLIS)),
@@ -0,0 +1,126 @@
+use entity_store::*;
+use grid_2d::Coord;
+use message_queues::PushMessages;
+use stats::{DamageSource, StatEvent};
+use tile::Tile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    Kinetic,
+    Piercing,
+    Blast,
+    Chemical,
+}
+
+const NUM_DAMAGE_TYPES: usize = 4;
+
+impl DamageType {
+    // armour only protects against some types of damage
+    pub fn blocked_by_kevlar(self) -> bool {
+        match self {
+            DamageType::Kinetic | DamageType::Piercing => true,
+            DamageType::Blast | DamageType::Chemical => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Damage {
+    pub amount: i32,
+    pub typ: DamageType,
+    pub source: DamageSource,
+}
+
+impl Damage {
+    pub fn new(amount: i32, typ: DamageType, source: DamageSource) -> Self {
+        Self {
+            amount,
+            typ,
+            source,
+        }
+    }
+}
+
+// Added to the amount of each type of damage an entity takes. Negative values
+// are resistances and positive values are weaknesses.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Resistances {
+    modifiers: [i32; NUM_DAMAGE_TYPES],
+}
+
+impl Resistances {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    pub fn with(mut self, typ: DamageType, modifier: i32) -> Self {
+        self.modifiers[typ as usize] = modifier;
+        self
+    }
+    pub fn modifier(&self, typ: DamageType) -> i32 {
+        self.modifiers[typ as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Hit {
+    pub tile: Tile,
+    pub coord: Coord,
+    pub damage: Damage,
+    pub modifier: i32,
+    pub dealt: i32,
+}
+
+// Reduces the health of `id` by the amount of damage remaining after its
+// resistances, and returns that amount
+pub fn apply<M: PushMessages>(
+    id: EntityId,
+    damage: Damage,
+    entity_store: &EntityStore,
+    messages: &mut M,
+) -> i32 {
+    apply_all(id, &[damage], entity_store, messages)
+}
+
+// Applies several damages to `id` with a single change to its health, as
+// separate changes in one batch would each start from the same health. Returns
+// the total amount dealt.
+pub fn apply_all<M: PushMessages>(
+    id: EntityId,
+    damages: &[Damage],
+    entity_store: &EntityStore,
+    messages: &mut M,
+) -> i32 {
+    let mut health = if let Some(&health) = entity_store.health_meter.get(&id) {
+        health
+    } else {
+        return 0;
+    };
+    let resistances = entity_store
+        .resistances
+        .get(&id)
+        .cloned()
+        .unwrap_or_default();
+    let mut total = 0;
+    for &damage in damages {
+        let modifier = resistances.modifier(damage.typ);
+        let dealt = ::std::cmp::max(damage.amount + modifier, 0);
+        total += dealt;
+        messages.change(insert::damaged_by(id, damage.source));
+        if let (Some(tile_info), Some(&coord)) =
+            (entity_store.tile_info.get(&id), entity_store.coord.get(&id))
+        {
+            messages.stat(StatEvent::Hit(Hit {
+                tile: tile_info.tile,
+                coord,
+                damage,
+                modifier,
+                dealt,
+            }));
+        }
+    }
+    if total > 0 {
+        health.value = ::std::cmp::max(health.value - total, 0);
+        messages.change(insert::health_meter(id, health));
+    }
+    total
+}
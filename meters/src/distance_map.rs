@@ -0,0 +1,325 @@
+use direction::*;
+use grid_2d::*;
+use grid_search::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+// A cardinal distance map from a single origin, which can be updated in place
+// when the origin moves or when the solidity of a single cell changes, rather
+// than being recomputed from scratch.
+#[derive(Debug, Clone)]
+pub struct IncrementalDistanceMap {
+    // costs are stored relative to `offset` so that every cost can be changed
+    // at once
+    costs: Grid<Option<u32>>,
+    offset: u32,
+    candidate: Grid<u64>,
+    affected: Grid<u64>,
+    seq: u64,
+    origin: Option<Coord>,
+    heap: BinaryHeap<Reverse<(u32, Coord)>>,
+    queue: VecDeque<Coord>,
+    affected_coords: Vec<Coord>,
+}
+
+fn is_open<G: SolidGrid>(grid: &G, coord: Coord) -> bool {
+    grid.is_solid(coord) == Some(false)
+}
+
+impl IncrementalDistanceMap {
+    pub fn new(size: Size) -> Self {
+        Self {
+            costs: Grid::new_clone(size, None),
+            offset: 0,
+            candidate: Grid::new_clone(size, 0),
+            affected: Grid::new_clone(size, 0),
+            seq: 0,
+            origin: None,
+            heap: BinaryHeap::new(),
+            queue: VecDeque::new(),
+            affected_coords: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.costs.size()
+    }
+
+    pub fn origin(&self) -> Option<Coord> {
+        self.origin
+    }
+
+    pub fn cost(&self, coord: Coord) -> Option<u32> {
+        self.costs
+            .get(coord)
+            .and_then(|&cost| cost)
+            .map(|cost| cost.wrapping_add(self.offset))
+    }
+
+    fn set_cost(&mut self, coord: Coord, cost: Option<u32>) {
+        let offset = self.offset;
+        *self.costs.get_checked_mut(coord) = cost.map(|cost| cost.wrapping_sub(offset));
+    }
+
+    // Recompute the entire map
+    pub fn populate<G: SolidGrid>(&mut self, grid: &G, origin: Coord) {
+        for cost in self.costs.iter_mut() {
+            *cost = None;
+        }
+        self.offset = 0;
+        self.origin = Some(origin);
+        if self.costs.get(origin).is_none() {
+            return;
+        }
+        self.set_cost(origin, Some(0));
+        self.queue.push_back(origin);
+        while let Some(coord) = self.queue.pop_front() {
+            let cost = self.cost(coord).expect("Missing cost");
+            for direction in CardinalDirections {
+                let neighbour = coord + direction.coord();
+                if is_open(grid, neighbour) && self.cost(neighbour).is_none() {
+                    self.set_cost(neighbour, Some(cost + 1));
+                    self.queue.push_back(neighbour);
+                }
+            }
+        }
+    }
+
+    // Move the origin one step, updating the map in place. Falls back to
+    // recomputing the entire map if the origin moves any other distance, or
+    // moves into or out of a solid cell.
+    pub fn move_origin<G: SolidGrid>(&mut self, grid: &G, origin: Coord) {
+        let previous = match self.origin {
+            Some(previous) => previous,
+            None => return self.populate(grid, origin),
+        };
+        if previous == origin {
+            return;
+        }
+        if self.cost(origin) != Some(1) || !is_open(grid, origin) || !is_open(grid, previous) {
+            return self.populate(grid, origin);
+        }
+
+        // The grid is bipartite, so every distance changes by exactly one.
+        // Cells whose shortest path from the previous origin went through the
+        // new origin get closer, and all other cells get further away.
+        self.seq += 1;
+        let seq = self.seq;
+        self.affected_coords.clear();
+        *self.candidate.get_checked_mut(origin) = seq;
+        self.queue.push_back(origin);
+        while let Some(coord) = self.queue.pop_front() {
+            self.affected_coords.push(coord);
+            let cost = self.cost(coord).expect("Missing cost");
+            for direction in CardinalDirections {
+                let neighbour = coord + direction.coord();
+                if self.cost(neighbour) == Some(cost + 1)
+                    && *self.candidate.get_checked(neighbour) != seq
+                {
+                    *self.candidate.get_checked_mut(neighbour) = seq;
+                    self.queue.push_back(neighbour);
+                }
+            }
+        }
+
+        self.offset = self.offset.wrapping_add(1);
+        for &coord in self.affected_coords.iter() {
+            if let Some(cost) = self.costs.get_checked_mut(coord).as_mut() {
+                *cost = cost.wrapping_sub(2);
+            }
+        }
+        self.origin = Some(origin);
+    }
+
+    // Update the map after the solidity of a single cell has changed
+    pub fn update_cell<G: SolidGrid>(&mut self, grid: &G, coord: Coord) {
+        if self.origin.is_none() || self.origin == Some(coord) {
+            return;
+        }
+        let current = if let Some(&current) = self.costs.get(coord) {
+            current
+        } else {
+            return;
+        };
+        let open = is_open(grid, coord);
+        match current {
+            Some(_) if !open => self.propagate_increase(grid, coord),
+            None if open => {
+                // start a new generation so no neighbours count as affected
+                self.seq += 1;
+                if let Some(cost) = self.best_neighbour_cost(coord) {
+                    self.set_cost(coord, Some(cost + 1));
+                    self.heap.push(Reverse((cost + 1, coord)));
+                    self.propagate_decrease(grid);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn best_neighbour_cost(&self, coord: Coord) -> Option<u32> {
+        CardinalDirections
+            .into_iter()
+            .filter_map(|direction| {
+                let neighbour = coord + direction.coord();
+                let affected = self
+                    .affected
+                    .get(neighbour)
+                    .map(|&seq| seq == self.seq)
+                    .unwrap_or(true);
+                if affected {
+                    None
+                } else {
+                    self.cost(neighbour)
+                }
+            })
+            .min()
+    }
+
+    // Relax the costs of cells reachable from the cells in the heap
+    fn propagate_decrease<G: SolidGrid>(&mut self, grid: &G) {
+        while let Some(Reverse((cost, coord))) = self.heap.pop() {
+            if self.cost(coord) != Some(cost) {
+                continue;
+            }
+            for direction in CardinalDirections {
+                let neighbour = coord + direction.coord();
+                if !is_open(grid, neighbour) {
+                    continue;
+                }
+                if self.cost(neighbour).map(|c| c > cost + 1).unwrap_or(true) {
+                    self.set_cost(neighbour, Some(cost + 1));
+                    self.heap.push(Reverse((cost + 1, neighbour)));
+                }
+            }
+        }
+    }
+
+    // `root` has become solid. Find every cell whose shortest path depended on it, and recompute
+    // their costs from the unaffected cells around them.
+    fn propagate_increase<G: SolidGrid>(&mut self, grid: &G, root: Coord) {
+        self.seq += 1;
+        let seq = self.seq;
+        self.affected_coords.clear();
+
+        if let Some(cost) = self.cost(root) {
+            *self.candidate.get_checked_mut(root) = seq;
+            self.heap.push(Reverse((cost, root)));
+        }
+
+        // visit candidates in order of their old cost, so all affected cells
+        // one step closer to the origin are known before each is considered
+        while let Some(Reverse((cost, coord))) = self.heap.pop() {
+            let supported = coord != root
+                && CardinalDirections.into_iter().any(|direction| {
+                    let neighbour = coord + direction.coord();
+                    self.cost(neighbour) == Some(cost - 1)
+                        && *self.affected.get_checked(neighbour) != seq
+                });
+            if supported {
+                continue;
+            }
+            *self.affected.get_checked_mut(coord) = seq;
+            self.affected_coords.push(coord);
+            for direction in CardinalDirections {
+                let neighbour = coord + direction.coord();
+                if self.cost(neighbour) == Some(cost + 1)
+                    && *self.candidate.get_checked(neighbour) != seq
+                {
+                    *self.candidate.get_checked_mut(neighbour) = seq;
+                    self.heap.push(Reverse((cost + 1, neighbour)));
+                }
+            }
+        }
+
+        for i in 0..self.affected_coords.len() {
+            let coord = self.affected_coords[i];
+            self.set_cost(coord, None);
+        }
+
+        for i in 0..self.affected_coords.len() {
+            let coord = self.affected_coords[i];
+            if !is_open(grid, coord) {
+                continue;
+            }
+            if let Some(cost) = self.best_neighbour_cost(coord) {
+                self.set_cost(coord, Some(cost + 1));
+                self.heap.push(Reverse((cost + 1, coord)));
+            }
+        }
+
+        self.propagate_decrease(grid);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    const NUM_SEEDS: u64 = 20;
+    const NUM_UPDATES: usize = 200;
+    const WALL_PROBABILITY: f64 = 0.3;
+
+    struct Walls(Grid<bool>);
+
+    impl SolidGrid for Walls {
+        fn is_solid(&self, coord: Coord) -> Option<bool> {
+            self.0.get(coord).cloned()
+        }
+    }
+
+    fn assert_matches_populate(walls: &Walls, distance_map: &IncrementalDistanceMap) {
+        let origin = distance_map.origin().expect("Missing origin");
+        let mut expected = IncrementalDistanceMap::new(distance_map.size());
+        expected.populate(walls, origin);
+        for (coord, _) in walls.0.enumerate() {
+            assert_eq!(
+                distance_map.cost(coord),
+                expected.cost(coord),
+                "cost mismatch at {:?} with origin {:?}",
+                coord,
+                origin
+            );
+        }
+    }
+
+    #[test]
+    fn random_steps_and_toggles_match_populate() {
+        let size = Size::new(12, 12);
+        for seed in 0..NUM_SEEDS {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut walls = Walls(Grid::new_fn(size, |_| rng.gen_bool(WALL_PROBABILITY)));
+            let mut origin = Coord::new(0, 0);
+            *walls.0.get_checked_mut(origin) = false;
+            let mut distance_map = IncrementalDistanceMap::new(size);
+            distance_map.populate(&walls, origin);
+            for _ in 0..NUM_UPDATES {
+                if rng.gen() {
+                    let directions = CardinalDirections
+                        .into_iter()
+                        .filter(|direction| is_open(&walls, origin + direction.coord()))
+                        .collect::<Vec<_>>();
+                    if let Some(direction) = directions.choose(&mut rng) {
+                        origin += direction.coord();
+                        distance_map.move_origin(&walls, origin);
+                    }
+                } else {
+                    let coord = Coord::new(
+                        rng.gen_range(0, size.x() as i32),
+                        rng.gen_range(0, size.y() as i32),
+                    );
+                    if coord == origin {
+                        continue;
+                    }
+                    let solid = walls.0.get_checked_mut(coord);
+                    *solid = !*solid;
+                    distance_map.update_cell(&walls, coord);
+                }
+                assert_matches_populate(&walls, &distance_map);
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use meter::*;
 use grid_search::*;
 use direction::*;
 use beacon::*;
+use boss;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum GoalType {
@@ -144,6 +145,7 @@ impl GoalState {
                     f(GoalMeterInfo {
                         typ: GoalMeterType::DistanceToExit,
                         meter: Meter::new(cell.cost() as i32, initial),
+                        boundaries: &[],
                     })
                 }
             }
@@ -157,6 +159,7 @@ impl GoalState {
                                     value: countdown,
                                     max: countdown_max,
                                 },
+                                boundaries: &[],
                             })
                         }
                     }
@@ -167,6 +170,7 @@ impl GoalState {
                     f(GoalMeterInfo {
                         typ: GoalMeterType::BossHealth,
                         meter: health,
+                        boundaries: boss::QUEEN_PHASE_BOUNDARIES,
                     })
                 }
             }
@@ -207,4 +211,6 @@ pub enum GoalMeterType {
 pub struct GoalMeterInfo {
     pub typ: GoalMeterType,
     pub meter: Meter,
+    // values at which something happens, e.g. the boss changing behaviour
+    pub boundaries: &'static [i32],
 }
@@ -22,28 +22,38 @@ mod message_queues;
 
 mod animation;
 mod beacon;
+mod boss;
 mod change;
 mod common_animations;
 mod event;
+mod light;
+mod noise;
 mod npc_info;
 mod pathfinding;
 mod pickup;
 mod policy;
 mod prototypes;
 mod pushed;
+mod scheduler;
 mod terrain;
 mod timing;
-mod transform;
 mod wave;
 mod weapons;
 mod world;
 
 pub mod alert;
+pub mod damage;
+pub mod distance_map;
 pub mod goal;
 pub mod input;
+pub mod loadout;
 pub mod meter;
 pub mod state;
+pub mod stats;
+pub mod status;
 pub mod tile;
 pub mod tile_info;
+pub mod transform;
+pub mod travel;
 
 pub use event::{AscendStatus, ExternalEvent};
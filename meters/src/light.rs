@@ -0,0 +1,118 @@
+use entity_store::*;
+use grid_2d::*;
+use shadowcast::{self, ShadowcastContext};
+
+pub const MAX_INTENSITY: u8 = 255;
+
+// how far the player can see lit cells
+pub const DEFAULT_VISION_RADIUS: u32 = 20;
+
+pub const PLAYER_LIGHT: Light = Light {
+    radius: 3,
+    intensity: 160,
+};
+pub const ROOM_LIGHT_INTENSITY: u8 = 200;
+pub const EGG_LIGHT: Light = Light {
+    radius: 1,
+    intensity: 120,
+};
+pub const SUPER_EGG_LIGHT: Light = Light {
+    radius: 2,
+    intensity: 200,
+};
+pub const MUZZLE_FLASH_LIGHT: Light = Light {
+    radius: 6,
+    intensity: MAX_INTENSITY,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Light {
+    pub radius: u32,
+    pub intensity: u8,
+}
+
+impl Light {
+    pub fn new(radius: u32, intensity: u8) -> Self {
+        Self { radius, intensity }
+    }
+
+    // light fades linearly with distance, reaching 0 just beyond its radius
+    fn intensity_at(&self, delta: Coord) -> u8 {
+        let distance = ((delta.x * delta.x + delta.y * delta.y) as f64).sqrt();
+        let range = self.radius as f64 + 1.;
+        if distance >= range {
+            0
+        } else {
+            (self.intensity as f64 * (range - distance) / range) as u8
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LightContext {
+    shadowcast: ShadowcastContext<u8>,
+    grid: Grid<u8>,
+    // the world count when the grid was last computed
+    updated_at: Option<u64>,
+}
+
+impl LightContext {
+    pub fn new(size: Size) -> Self {
+        Self {
+            shadowcast: ShadowcastContext::new(),
+            grid: Grid::new_default(size),
+            updated_at: None,
+        }
+    }
+
+    // Forces the next update to recompute the grid, e.g. after the world has
+    // been replaced and its count may repeat
+    pub fn invalidate(&mut self) {
+        self.updated_at = None;
+    }
+
+    // Recomputes the light level of every cell. Light is blocked by opaque
+    // cells, and where several lights overlap the brightest one wins. Lights
+    // and walls only change when the world does, so the grid is kept until
+    // `time` (the world count) moves on.
+    pub fn update(
+        &mut self,
+        time: u64,
+        entity_store: &EntityStore,
+        spatial_hash: &SpatialHashTable,
+    ) {
+        if self.updated_at == Some(time) {
+            return;
+        }
+        self.updated_at = Some(time);
+        for cell in self.grid.iter_mut() {
+            *cell = 0;
+        }
+        for (id, &light) in entity_store.light.iter() {
+            let centre = if let Some(&coord) = entity_store.coord.get(id) {
+                coord
+            } else {
+                continue;
+            };
+            let grid = &mut self.grid;
+            self.shadowcast.for_each(
+                centre,
+                spatial_hash,
+                shadowcast::vision_distance::Circle::new(light.radius),
+                1,
+                |coord, _, _| {
+                    if let Some(cell) = grid.get_mut(coord) {
+                        let intensity = light.intensity_at(coord - centre);
+                        if intensity > *cell {
+                            *cell = intensity;
+                        }
+                    }
+                },
+            );
+        }
+    }
+
+    pub fn get(&self, coord: Coord) -> u8 {
+        self.grid.get(coord).cloned().unwrap_or(0)
+    }
+}
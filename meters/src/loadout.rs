@@ -0,0 +1,174 @@
+use input::ActiveMeterIdentifier;
+use meter::ActiveMeterType;
+use std::collections::BTreeMap;
+
+pub const NUM_SLOTS: usize = 10;
+
+// pins are kept even while the player doesn't have the meter, so they can be
+// carried from one run to the next
+pub type LoadoutPins = BTreeMap<ActiveMeterType, usize>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Loadout {
+    slots: Vec<Option<ActiveMeterType>>,
+    pins: LoadoutPins,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoadoutSlot {
+    pub identifier: ActiveMeterIdentifier,
+    pub typ: Option<ActiveMeterType>,
+    pub is_pinned: bool,
+}
+
+pub struct LoadoutIter<'a> {
+    loadout: &'a Loadout,
+    index: usize,
+}
+
+impl<'a> Iterator for LoadoutIter<'a> {
+    type Item = LoadoutSlot;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= NUM_SLOTS {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+        let typ = self.loadout.slots[index];
+        Some(LoadoutSlot {
+            identifier: ActiveMeterIdentifier::from_index(index),
+            typ,
+            is_pinned: typ
+                .map(|typ| self.loadout.pins.get(&typ) == Some(&index))
+                .unwrap_or(false),
+        })
+    }
+}
+
+pub struct LoadoutMeterIter<'a>(LoadoutIter<'a>);
+
+impl<'a> Iterator for LoadoutMeterIter<'a> {
+    type Item = (ActiveMeterIdentifier, ActiveMeterType);
+    fn next(&mut self) -> Option<Self::Item> {
+        for slot in self.0.by_ref() {
+            if let Some(typ) = slot.typ {
+                return Some((slot.identifier, typ));
+            }
+        }
+        None
+    }
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Loadout {
+    pub fn new() -> Self {
+        Self {
+            slots: vec![None; NUM_SLOTS],
+            pins: LoadoutPins::new(),
+        }
+    }
+
+    pub fn get(&self, identifier: ActiveMeterIdentifier) -> Option<ActiveMeterType> {
+        self.slots[identifier.to_index()]
+    }
+
+    pub fn contains(&self, typ: ActiveMeterType) -> bool {
+        self.slots.contains(&Some(typ))
+    }
+
+    pub fn pins(&self) -> &LoadoutPins {
+        &self.pins
+    }
+
+    pub fn slots(&self) -> LoadoutIter<'_> {
+        LoadoutIter {
+            loadout: self,
+            index: 0,
+        }
+    }
+
+    pub fn meters(&self) -> LoadoutMeterIter<'_> {
+        LoadoutMeterIter(self.slots())
+    }
+
+    fn is_reserved(&self, index: usize) -> bool {
+        self.pins.values().any(|&pinned| pinned == index)
+    }
+
+    pub fn insert(&mut self, typ: ActiveMeterType) {
+        if self.contains(typ) {
+            return;
+        }
+        if let Some(&index) = self.pins.get(&typ) {
+            if self.slots[index].is_none() {
+                self.slots[index] = Some(typ);
+                return;
+            }
+        }
+        let index = (0..NUM_SLOTS)
+            .find(|&index| self.slots[index].is_none() && !self.is_reserved(index))
+            .or_else(|| (0..NUM_SLOTS).find(|&index| self.slots[index].is_none()));
+        if let Some(index) = index {
+            self.slots[index] = Some(typ);
+        }
+    }
+
+    pub fn remove(&mut self, typ: ActiveMeterType) {
+        for slot in self.slots.iter_mut() {
+            if *slot == Some(typ) {
+                *slot = None;
+            }
+        }
+    }
+
+    pub fn swap(&mut self, a: ActiveMeterIdentifier, b: ActiveMeterIdentifier) {
+        let (a, b) = (a.to_index(), b.to_index());
+        let pinned = |typ: Option<ActiveMeterType>, pins: &LoadoutPins| {
+            typ.filter(|typ| pins.contains_key(typ))
+        };
+        let pinned_a = pinned(self.slots[a], &self.pins);
+        let pinned_b = pinned(self.slots[b], &self.pins);
+        self.slots.swap(a, b);
+        if let Some(typ) = pinned_a {
+            self.pin(typ, b);
+        }
+        if let Some(typ) = pinned_b {
+            self.pin(typ, a);
+        }
+    }
+
+    fn pin(&mut self, typ: ActiveMeterType, index: usize) {
+        self.pins.retain(|_, &mut pinned| pinned != index);
+        self.pins.insert(typ, index);
+    }
+
+    pub fn toggle_pin(&mut self, identifier: ActiveMeterIdentifier) {
+        let index = identifier.to_index();
+        if let Some(typ) = self.slots[index] {
+            if self.pins.get(&typ) == Some(&index) {
+                self.pins.remove(&typ);
+            } else {
+                self.pin(typ, index);
+            }
+        }
+    }
+
+    pub fn set_pins(&mut self, pins: LoadoutPins) {
+        let (pinned, unpinned): (Vec<_>, Vec<_>) = self
+            .meters()
+            .map(|(_, typ)| typ)
+            .partition(|typ| pins.contains_key(typ));
+        self.pins = pins;
+        for slot in self.slots.iter_mut() {
+            *slot = None;
+        }
+        for typ in pinned.into_iter().chain(unpinned) {
+            self.insert(typ);
+        }
+    }
+}
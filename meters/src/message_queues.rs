@@ -1,6 +1,9 @@
 use entity_store::*;
 use animation::*;
 use alert::*;
+use noise::Noise;
+use stats::StatEvent;
+use status::StatusChange;
 
 macro_rules! swap_drain {
     ($field:ident, $current:expr, $swap:expr) => {
@@ -41,6 +44,10 @@ pub struct MessageQueues {
     pub removed_entities: Vec<EntityId>,
     pub player_moved_to: Option<Coord>,
     pub special: Option<Special>,
+    pub noises: Vec<Noise>,
+    pub solidity_changed: Vec<Coord>,
+    pub stat_events: Vec<StatEvent>,
+    pub status_changes: Vec<StatusChange>,
 }
 
 impl MessageQueues {
@@ -51,6 +58,10 @@ impl MessageQueues {
             removed_entities: Vec::new(),
             player_moved_to: None,
             special: None,
+            noises: Vec::new(),
+            solidity_changed: Vec::new(),
+            stat_events: Vec::new(),
+            status_changes: Vec::new(),
         }
     }
     pub fn clear(&mut self) {
@@ -59,6 +70,10 @@ impl MessageQueues {
         self.removed_entities.clear();
         self.player_moved_to = None;
         self.special = None;
+        self.noises.clear();
+        self.solidity_changed.clear();
+        self.stat_events.clear();
+        self.status_changes.clear();
     }
 }
 
@@ -71,6 +86,9 @@ pub trait PushMessages {
     fn win(&mut self);
     fn ascend(&mut self);
     fn alert(&mut self, alert: Alert);
+    fn noise(&mut self, coord: Coord, radius: u32);
+    fn stat(&mut self, event: StatEvent);
+    fn status(&mut self, change: StatusChange);
 }
 
 impl PushMessages for MessageQueues {
@@ -98,4 +116,13 @@ impl PushMessages for MessageQueues {
     fn alert(&mut self, alert: Alert) {
         self.special = Some(Special::Alert(alert));
     }
+    fn noise(&mut self, coord: Coord, radius: u32) {
+        self.noises.push(Noise { coord, radius });
+    }
+    fn stat(&mut self, event: StatEvent) {
+        self.stat_events.push(event);
+    }
+    fn status(&mut self, change: StatusChange) {
+        self.status_changes.push(change);
+    }
 }
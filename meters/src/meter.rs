@@ -18,6 +18,13 @@ pub enum MeterType {
     Metabol,
     Blink,
     Push,
+    Grenade,
+    Flare,
+    Decoy,
+    Shield,
+    Haste,
+    Regeneration,
+    Stealth,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -28,6 +35,11 @@ pub enum ActiveMeterType {
     Metabol,
     Push,
     Blink,
+    Grenade,
+    Flare,
+    Decoy,
+    Shield,
+    Haste,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -36,6 +48,8 @@ pub enum PassiveMeterType {
     Kevlar,
     Stamina,
     Compass,
+    Regeneration,
+    Stealth,
 }
 
 impl ActiveMeterType {
@@ -59,6 +73,11 @@ impl From<ActiveMeterType> for MeterType {
             ActiveMeterType::Blink => MeterType::Blink,
             ActiveMeterType::Metabol => MeterType::Metabol,
             ActiveMeterType::Push => MeterType::Push,
+            ActiveMeterType::Grenade => MeterType::Grenade,
+            ActiveMeterType::Flare => MeterType::Flare,
+            ActiveMeterType::Decoy => MeterType::Decoy,
+            ActiveMeterType::Shield => MeterType::Shield,
+            ActiveMeterType::Haste => MeterType::Haste,
         }
     }
 }
@@ -70,6 +89,8 @@ impl From<PassiveMeterType> for MeterType {
             PassiveMeterType::Kevlar => MeterType::Kevlar,
             PassiveMeterType::Stamina => MeterType::Stamina,
             PassiveMeterType::Compass => MeterType::Compass,
+            PassiveMeterType::Regeneration => MeterType::Regeneration,
+            PassiveMeterType::Stealth => MeterType::Stealth,
         }
     }
 }
@@ -82,11 +103,18 @@ impl MeterType {
             ComponentType::BlinkMeter => Some(MeterType::Blink),
             ComponentType::MetabolMeter => Some(MeterType::Metabol),
             ComponentType::PushMeter => Some(MeterType::Push),
+            ComponentType::GrenadeMeter => Some(MeterType::Grenade),
+            ComponentType::FlareMeter => Some(MeterType::Flare),
+            ComponentType::DecoyMeter => Some(MeterType::Decoy),
+            ComponentType::ShieldMeter => Some(MeterType::Shield),
+            ComponentType::HasteMeter => Some(MeterType::Haste),
             ComponentType::StaminaMeter => Some(MeterType::Stamina),
             ComponentType::RailGunMeter => Some(MeterType::RailGun),
             ComponentType::HealthMeter => Some(MeterType::Health),
             ComponentType::KevlarMeter => Some(MeterType::Kevlar),
             ComponentType::CompassMeter => Some(MeterType::Compass),
+            ComponentType::RegenerationMeter => Some(MeterType::Regeneration),
+            ComponentType::StealthMeter => Some(MeterType::Stealth),
             _ => None,
         }
     }
@@ -98,10 +126,17 @@ impl MeterType {
             MeterType::Blink => ActiveOrPassive::Active(ActiveMeterType::Blink),
             MeterType::Metabol => ActiveOrPassive::Active(ActiveMeterType::Metabol),
             MeterType::Push => ActiveOrPassive::Active(ActiveMeterType::Push),
+            MeterType::Grenade => ActiveOrPassive::Active(ActiveMeterType::Grenade),
+            MeterType::Flare => ActiveOrPassive::Active(ActiveMeterType::Flare),
+            MeterType::Decoy => ActiveOrPassive::Active(ActiveMeterType::Decoy),
+            MeterType::Shield => ActiveOrPassive::Active(ActiveMeterType::Shield),
+            MeterType::Haste => ActiveOrPassive::Active(ActiveMeterType::Haste),
             MeterType::Stamina => ActiveOrPassive::Passive(PassiveMeterType::Stamina),
             MeterType::Health => ActiveOrPassive::Passive(PassiveMeterType::Health),
             MeterType::Kevlar => ActiveOrPassive::Passive(PassiveMeterType::Kevlar),
             MeterType::Compass => ActiveOrPassive::Passive(PassiveMeterType::Compass),
+            MeterType::Regeneration => ActiveOrPassive::Passive(PassiveMeterType::Regeneration),
+            MeterType::Stealth => ActiveOrPassive::Passive(PassiveMeterType::Stealth),
         }
     }
     pub fn active(self) -> Option<ActiveMeterType> {
@@ -125,10 +160,17 @@ impl MeterType {
             MeterType::Blink => 8,
             MeterType::Metabol => 4,
             MeterType::Push => 4,
+            MeterType::Grenade => 4,
+            MeterType::Flare => 2,
+            MeterType::Decoy => 20,
+            MeterType::Shield => 8,
+            MeterType::Haste => 16,
             MeterType::Stamina => 6,
             MeterType::Health => 10,
             MeterType::Kevlar => 10,
             MeterType::Compass => 100,
+            MeterType::Regeneration => 8,
+            MeterType::Stealth => 6,
         }
     }
     pub fn player_component_value(self) -> ComponentValue {
@@ -141,10 +183,17 @@ impl MeterType {
             MeterType::Blink => ComponentValue::BlinkMeter(Meter::new(initial, max)),
             MeterType::Metabol => ComponentValue::MetabolMeter(Meter::new(initial, max)),
             MeterType::Push => ComponentValue::PushMeter(Meter::new(initial, max)),
+            MeterType::Grenade => ComponentValue::GrenadeMeter(Meter::new(initial, max)),
+            MeterType::Flare => ComponentValue::FlareMeter(Meter::new(initial, max)),
+            MeterType::Decoy => ComponentValue::DecoyMeter(Meter::new(initial, max)),
+            MeterType::Shield => ComponentValue::ShieldMeter(Meter::new(initial, max)),
+            MeterType::Haste => ComponentValue::HasteMeter(Meter::new(initial, max)),
             MeterType::Stamina => ComponentValue::StaminaMeter(Meter::new(initial, max)),
             MeterType::Health => ComponentValue::HealthMeter(Meter::full(max)),
             MeterType::Kevlar => ComponentValue::KevlarMeter(Meter::new(initial, max)),
             MeterType::Compass => ComponentValue::CompassMeter(Meter::new(initial, max)),
+            MeterType::Regeneration => ComponentValue::RegenerationMeter(Meter::empty(max)),
+            MeterType::Stealth => ComponentValue::StealthMeter(Meter::new(initial, max)),
         }
     }
     pub fn is_active(self) -> bool {
@@ -155,10 +204,17 @@ impl MeterType {
             MeterType::Blink => true,
             MeterType::Metabol => true,
             MeterType::Push => true,
+            MeterType::Grenade => true,
+            MeterType::Flare => true,
+            MeterType::Decoy => true,
+            MeterType::Shield => true,
+            MeterType::Haste => true,
             MeterType::Stamina => false,
             MeterType::Health => false,
             MeterType::Kevlar => false,
             MeterType::Compass => false,
+            MeterType::Regeneration => false,
+            MeterType::Stealth => false,
         }
     }
     pub fn insert(self, id: EntityId, meter: Meter) -> EntityChange {
@@ -169,10 +225,17 @@ impl MeterType {
             MeterType::Blink => insert::blink_meter(id, meter),
             MeterType::Metabol => insert::metabol_meter(id, meter),
             MeterType::Push => insert::push_meter(id, meter),
+            MeterType::Grenade => insert::grenade_meter(id, meter),
+            MeterType::Flare => insert::flare_meter(id, meter),
+            MeterType::Decoy => insert::decoy_meter(id, meter),
+            MeterType::Shield => insert::shield_meter(id, meter),
+            MeterType::Haste => insert::haste_meter(id, meter),
             MeterType::Health => insert::health_meter(id, meter),
             MeterType::Stamina => insert::stamina_meter(id, meter),
             MeterType::Kevlar => insert::kevlar_meter(id, meter),
             MeterType::Compass => insert::compass_meter(id, meter),
+            MeterType::Regeneration => insert::regeneration_meter(id, meter),
+            MeterType::Stealth => insert::stealth_meter(id, meter),
         }
     }
     pub fn periodic_change(self) -> Option<PeriodicChange> {
@@ -195,7 +258,29 @@ impl MeterType {
             MeterType::Kevlar => None,
             MeterType::Metabol => None,
             MeterType::Push => None,
+            MeterType::Grenade => None,
+            MeterType::Flare => None,
+            MeterType::Decoy => Some(PeriodicChange {
+                turns: 0,
+                change: 1,
+            }),
+            MeterType::Shield => Some(PeriodicChange {
+                turns: 0,
+                change: 1,
+            }),
+            MeterType::Haste => Some(PeriodicChange {
+                turns: 0,
+                change: 1,
+            }),
             MeterType::Compass => None,
+            MeterType::Regeneration => Some(PeriodicChange {
+                turns: 0,
+                change: 1,
+            }),
+            MeterType::Stealth => Some(PeriodicChange {
+                turns: 3,
+                change: 1,
+            }),
         }
     }
 }
@@ -209,10 +294,17 @@ impl From<MeterType> for ComponentType {
             MeterType::Blink => ComponentType::BlinkMeter,
             MeterType::Metabol => ComponentType::MetabolMeter,
             MeterType::Push => ComponentType::PushMeter,
+            MeterType::Grenade => ComponentType::GrenadeMeter,
+            MeterType::Flare => ComponentType::FlareMeter,
+            MeterType::Decoy => ComponentType::DecoyMeter,
+            MeterType::Shield => ComponentType::ShieldMeter,
+            MeterType::Haste => ComponentType::HasteMeter,
             MeterType::Health => ComponentType::HealthMeter,
             MeterType::Stamina => ComponentType::StaminaMeter,
             MeterType::Kevlar => ComponentType::KevlarMeter,
             MeterType::Compass => ComponentType::CompassMeter,
+            MeterType::Regeneration => ComponentType::RegenerationMeter,
+            MeterType::Stealth => ComponentType::StealthMeter,
         }
     }
 }
@@ -223,10 +315,17 @@ pub const ALL_METER_TYPES: &[MeterType] = &[
     MeterType::Medkit,
     MeterType::Metabol,
     MeterType::Push,
+    MeterType::Grenade,
+    MeterType::Flare,
+    MeterType::Decoy,
+    MeterType::Shield,
+    MeterType::Haste,
     MeterType::Health,
     MeterType::Stamina,
     MeterType::Kevlar,
     MeterType::Compass,
+    MeterType::Regeneration,
+    MeterType::Stealth,
     MeterType::Blink,
 ];
 
@@ -265,7 +364,14 @@ impl Meter {
             ComponentRef::BlinkMeter(meter) => Some(*meter),
             ComponentRef::MetabolMeter(meter) => Some(*meter),
             ComponentRef::PushMeter(meter) => Some(*meter),
+            ComponentRef::GrenadeMeter(meter) => Some(*meter),
+            ComponentRef::FlareMeter(meter) => Some(*meter),
+            ComponentRef::DecoyMeter(meter) => Some(*meter),
+            ComponentRef::ShieldMeter(meter) => Some(*meter),
+            ComponentRef::HasteMeter(meter) => Some(*meter),
             ComponentRef::CompassMeter(meter) => Some(*meter),
+            ComponentRef::RegenerationMeter(meter) => Some(*meter),
+            ComponentRef::StealthMeter(meter) => Some(*meter),
             _ => None,
         }
     }
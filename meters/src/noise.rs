@@ -0,0 +1,86 @@
+use direction::*;
+use entity_store::*;
+use grid_search::*;
+use rand::Rng;
+
+pub const GUN_NOISE_RADIUS: u32 = 10;
+pub const RAIL_GUN_NOISE_RADIUS: u32 = 14;
+pub const GRENADE_NOISE_RADIUS: u32 = 16;
+pub const PUNCH_NOISE_RADIUS: u32 = 4;
+pub const DOOR_NOISE_RADIUS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Noise {
+    pub coord: Coord,
+    pub radius: u32,
+}
+
+// sound travels through open space and doors, but not walls
+struct SpatialHashSoundGrid<'a> {
+    grid: &'a SpatialHashTable,
+}
+
+impl<'a> SolidGrid for SpatialHashSoundGrid<'a> {
+    fn is_solid(&self, coord: Coord) -> Option<bool> {
+        self.grid
+            .get(coord)
+            .map(|cell| cell.solid_count > 0 && cell.door_count == 0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NoiseContext {
+    bfs: BfsContext,
+    distance_map: UniformDistanceMap<u32, DirectionsCardinal>,
+}
+
+impl NoiseContext {
+    pub fn new(size: Size) -> Self {
+        Self {
+            bfs: BfsContext::new(size),
+            distance_map: UniformDistanceMap::new(size, DirectionsCardinal),
+        }
+    }
+
+    // Calls `f` with each sleeping npc that hears the noise. Npcs closer to the
+    // source of the noise are more likely to hear it.
+    pub fn for_each_woken_npc<R, F>(
+        &mut self,
+        noise: Noise,
+        entity_store: &EntityStore,
+        spatial_hash: &SpatialHashTable,
+        rng: &mut R,
+        mut f: F,
+    ) where
+        R: Rng,
+        F: FnMut(EntityId),
+    {
+        const CONFIG: SearchConfig = SearchConfig {
+            allow_solid_start: true,
+        };
+
+        self.bfs
+            .populate_uniform_distance_map(
+                &SpatialHashSoundGrid { grid: spatial_hash },
+                noise.coord,
+                CONFIG,
+                &mut self.distance_map,
+            )
+            .expect("Failed to propagate noise");
+
+        for (id, info) in entity_store.npc.iter() {
+            if info.active {
+                continue;
+            }
+            let distance = entity_store
+                .coord
+                .get(id)
+                .and_then(|&coord| self.distance_map.cost(coord));
+            if let Some(distance) = distance {
+                if distance <= noise.radius && rng.gen_range(0, noise.radius + 1) >= distance {
+                    f(*id);
+                }
+            }
+        }
+    }
+}
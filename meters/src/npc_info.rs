@@ -3,5 +3,4 @@ pub struct NpcInfo {
     pub active: bool,
     pub boss: bool,
     pub mobile: bool,
-    pub fast: bool,
 }
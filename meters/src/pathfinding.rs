@@ -1,11 +1,64 @@
 use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
 use grid_search::*;
 use entity_store::*;
 use direction::*;
+use distance_map::IncrementalDistanceMap;
+use grid_2d::Grid;
 use message_queues::PushMessages;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
+#[derive(Debug, Clone, Copy)]
 pub struct PathfindingConfig {
     pub open_doors: bool,
+    // spread out to surround the player rather than all taking the same route
+    pub cooperative: bool,
+}
+
+impl PathfindingConfig {
+    pub fn for_entity(id: EntityId, entity_store: &EntityStore) -> Self {
+        Self {
+            open_doors: entity_store.door_opener.contains(&id),
+            cooperative: entity_store.cooperative.contains(&id),
+        }
+    }
+}
+
+// Where an npc is heading when no attractor is in range
+#[derive(Debug, Clone, Copy)]
+pub enum NpcTarget {
+    Player,
+    LastKnown(Coord),
+    Wander,
+}
+
+// Something other than the player that npcs will path towards. Npcs within
+// range of an attractor follow the one with the highest priority, falling back
+// to the player if none are in range.
+#[derive(Debug, Clone, Copy)]
+pub struct Attractor {
+    pub coord: Coord,
+    pub priority: u32,
+    pub range: u32,
+}
+
+#[derive(Debug, Clone)]
+struct AttractorMap {
+    attractor: Attractor,
+    distance_map: UniformDistanceMap<u32, DirectionsCardinal>,
+}
+
+// A free cell next to the player which a cooperative npc can claim. Each slot
+// stays on the same side of the player, so its distance map can be updated
+// incrementally as the player moves.
+#[derive(Debug, Clone)]
+struct FlankSlot {
+    direction: CardinalDirection,
+    // false if the cell is solid, or its distance map must be recomputed
+    valid: bool,
+    claimed: bool,
+    distance_map: IncrementalDistanceMap,
 }
 
 struct SpatialHashSolidCellGrid<'a> {
@@ -25,6 +78,33 @@ impl<'a> SolidGrid for SpatialHashSolidCellGrid<'a> {
     }
 }
 
+impl<'a> CostGrid for SpatialHashSolidCellGrid<'a> {
+    type Cost = u32;
+    fn cost(&self, coord: Coord, _direction: Direction) -> Option<CostCell<Self::Cost>> {
+        self.is_solid(coord).map(|solid| {
+            if solid {
+                CostCell::Solid
+            } else {
+                CostCell::Cost(1)
+            }
+        })
+    }
+}
+
+// Also treats cells that cooperative npcs intend to move through as solid
+struct ReservedCellGrid<'a> {
+    grid: SpatialHashSolidOrOccupiedCellGrid<'a>,
+    reserved: &'a HashSet<Coord>,
+}
+
+impl<'a> SolidGrid for ReservedCellGrid<'a> {
+    fn is_solid(&self, coord: Coord) -> Option<bool> {
+        self.grid
+            .is_solid(coord)
+            .map(|solid| solid || self.reserved.contains(&coord))
+    }
+}
+
 impl<'a> SolidGrid for SpatialHashSolidOrOccupiedCellGrid<'a> {
     fn is_solid(&self, coord: Coord) -> Option<bool> {
         self.grid.get(coord).map(|cell| {
@@ -34,13 +114,88 @@ impl<'a> SolidGrid for SpatialHashSolidOrOccupiedCellGrid<'a> {
     }
 }
 
+enum TargetMap<'a> {
+    Incremental(&'a IncrementalDistanceMap),
+    Uniform(&'a UniformDistanceMap<u32, DirectionsCardinal>),
+}
+
+impl<'a> TargetMap<'a> {
+    fn cost(&self, coord: Coord) -> Option<u32> {
+        match *self {
+            TargetMap::Incremental(distance_map) => distance_map.cost(coord),
+            TargetMap::Uniform(distance_map) => distance_map.cost(coord),
+        }
+    }
+}
+
+// Direction of the first step towards the lowest cost cell within `max_depth`
+// steps of `start`, preferring nearer cells when costs are equal. Cells are
+// marked as seen by setting them to `seq`, which must differ from every
+// previous call.
+fn best_step<G: SolidGrid>(
+    grid: &G,
+    start: Coord,
+    max_depth: u32,
+    distance_map: TargetMap,
+    queue: &mut VecDeque<(Coord, u32, CardinalDirection)>,
+    seen: &mut Grid<u64>,
+    seq: u64,
+) -> Option<CardinalDirection> {
+    let mut best_cost = distance_map.cost(start)?;
+    let mut best_direction = None;
+
+    queue.clear();
+    if let Some(cell) = seen.get_mut(start) {
+        *cell = seq;
+    }
+
+    for direction in CardinalDirections {
+        queue.push_back((start + direction.coord(), 1, direction));
+    }
+
+    while let Some((coord, depth, first_direction)) = queue.pop_front() {
+        if grid.is_solid(coord) != Some(false) {
+            continue;
+        }
+        match seen.get_mut(coord) {
+            Some(cell) if *cell != seq => *cell = seq,
+            _ => continue,
+        }
+        if let Some(cost) = distance_map.cost(coord) {
+            if cost < best_cost {
+                best_cost = cost;
+                best_direction = Some(first_direction);
+            }
+        }
+        if depth < max_depth {
+            for direction in CardinalDirections {
+                queue.push_back((coord + direction.coord(), depth + 1, first_direction));
+            }
+        }
+    }
+
+    best_direction
+}
+
 #[derive(Debug, Clone)]
 pub struct PathfindingContext {
     search: SearchContext<u32>,
     bfs: BfsContext,
-    distance_map: UniformDistanceMap<u32, DirectionsCardinal>,
-    distance_map_open_doors: UniformDistanceMap<u32, DirectionsCardinal>,
+    distance_map: IncrementalDistanceMap,
+    distance_map_open_doors: IncrementalDistanceMap,
+    attractor_maps: Vec<AttractorMap>,
+    num_attractors: usize,
+    // attractors don't move, so their maps are kept until the set of
+    // attractors or the solidity of a cell changes
+    attractors_stale: bool,
+    next_attractors: Vec<Attractor>,
     path: Vec<Direction>,
+    best_step_queue: VecDeque<(Coord, u32, CardinalDirection)>,
+    best_step_seen: Grid<u64>,
+    best_step_seq: u64,
+    flank_slots: Vec<FlankSlot>,
+    flank_origin: Option<Coord>,
+    reserved: HashSet<Coord>,
 }
 
 impl PathfindingContext {
@@ -48,100 +203,343 @@ impl PathfindingContext {
         Self {
             search: SearchContext::new(size),
             bfs: BfsContext::new(size),
-            distance_map: UniformDistanceMap::new(size, DirectionsCardinal),
-            distance_map_open_doors: UniformDistanceMap::new(size, DirectionsCardinal),
+            distance_map: IncrementalDistanceMap::new(size),
+            distance_map_open_doors: IncrementalDistanceMap::new(size),
+            attractor_maps: Vec::new(),
+            num_attractors: 0,
+            attractors_stale: true,
+            next_attractors: Vec::new(),
             path: Vec::new(),
+            best_step_queue: VecDeque::new(),
+            best_step_seen: Grid::new_clone(size, 0),
+            best_step_seq: 0,
+            flank_slots: CardinalDirections
+                .into_iter()
+                .map(|direction| FlankSlot {
+                    direction,
+                    valid: false,
+                    claimed: false,
+                    distance_map: IncrementalDistanceMap::new(size),
+                })
+                .collect(),
+            flank_origin: None,
+            reserved: HashSet::new(),
         }
     }
 
     pub fn distance_to_player(&self, coord: Coord) -> Option<u32> {
-        if let Some(cell) = self.distance_map_open_doors.get(coord).cell() {
-            Some(cell.cost())
-        } else {
-            None
+        self.distance_map_open_doors.cost(coord)
+    }
+
+    pub fn populate_player_map(&mut self, player_coord: Coord, spatial_hash: &SpatialHashTable) {
+        self.flank_origin = None;
+        for slot in self.flank_slots.iter_mut() {
+            slot.valid = false;
         }
+        self.attractors_stale = true;
+        self.distance_map.populate(
+            &SpatialHashSolidCellGrid {
+                grid: spatial_hash,
+                open_doors: false,
+            },
+            player_coord,
+        );
+        self.distance_map_open_doors.populate(
+            &SpatialHashSolidCellGrid {
+                grid: spatial_hash,
+                open_doors: true,
+            },
+            player_coord,
+        );
     }
 
+    // Moving a single step or staying put is handled incrementally. Anything
+    // else recomputes the maps from scratch.
     pub fn update_player_map(&mut self, player_coord: Coord, spatial_hash: &SpatialHashTable) {
-        self.bfs
-            .populate_uniform_distance_map(
-                &SpatialHashSolidCellGrid {
-                    grid: spatial_hash,
-                    open_doors: true,
-                },
-                player_coord,
-                Default::default(),
-                &mut self.distance_map,
-            )
-            .expect("Failed to update player distance map");
-        self.bfs
-            .populate_uniform_distance_map(
-                &SpatialHashSolidCellGrid {
-                    grid: spatial_hash,
-                    open_doors: true,
-                },
-                player_coord,
-                Default::default(),
-                &mut self.distance_map_open_doors,
-            )
-            .expect("Failed to update player distance map");
-    }
-
-    pub fn act<M>(
+        let grid = SpatialHashSolidCellGrid {
+            grid: spatial_hash,
+            open_doors: false,
+        };
+        let grid_open_doors = SpatialHashSolidCellGrid {
+            grid: spatial_hash,
+            open_doors: true,
+        };
+        let is_step = self
+            .distance_map_open_doors
+            .origin()
+            .map(|origin| (player_coord - origin).manhattan_magnitude() <= 1)
+            .unwrap_or(false);
+        if is_step {
+            self.distance_map.move_origin(&grid, player_coord);
+            self.distance_map_open_doors
+                .move_origin(&grid_open_doors, player_coord);
+        } else {
+            self.populate_player_map(player_coord, spatial_hash);
+        }
+    }
+
+    // Call when the solidity of a cell has changed
+    pub fn update_cell(&mut self, coord: Coord, spatial_hash: &SpatialHashTable) {
+        self.attractors_stale = true;
+        self.flank_origin = None;
+        let grid = SpatialHashSolidCellGrid {
+            grid: spatial_hash,
+            open_doors: false,
+        };
+        let grid_open_doors = SpatialHashSolidCellGrid {
+            grid: spatial_hash,
+            open_doors: true,
+        };
+        self.distance_map.update_cell(&grid, coord);
+        self.distance_map_open_doors
+            .update_cell(&grid_open_doors, coord);
+        for slot in self.flank_slots.iter_mut() {
+            if slot.distance_map.origin() == Some(coord) {
+                slot.valid = false;
+            } else if slot.valid {
+                slot.distance_map.update_cell(&grid_open_doors, coord);
+            }
+        }
+    }
+
+    fn player_map(&self, config: PathfindingConfig) -> &IncrementalDistanceMap {
+        if config.open_doors {
+            &self.distance_map_open_doors
+        } else {
+            &self.distance_map
+        }
+    }
+
+    // Call before each round of npc turns
+    pub fn clear_reservations(&mut self) {
+        self.reserved.clear();
+        for slot in self.flank_slots.iter_mut() {
+            slot.claimed = false;
+        }
+    }
+
+    fn update_flank_slots(&mut self, player_coord: Coord, spatial_hash: &SpatialHashTable) {
+        if self.flank_origin == Some(player_coord) {
+            return;
+        }
+        self.flank_origin = Some(player_coord);
+        let grid = SpatialHashSolidCellGrid {
+            grid: spatial_hash,
+            open_doors: true,
+        };
+        for slot in self.flank_slots.iter_mut() {
+            let slot_coord = player_coord + slot.direction.coord();
+            if grid.is_solid(slot_coord) != Some(false) {
+                slot.valid = false;
+            } else if slot.valid {
+                // recomputes from scratch unless the slot moved a single step
+                slot.distance_map.move_origin(&grid, slot_coord);
+            } else {
+                slot.distance_map.populate(&grid, slot_coord);
+                slot.valid = true;
+            }
+        }
+    }
+
+    // Head for the nearest free cell next to the player that no other
+    // cooperative npc has claimed this turn, avoiding cells other cooperative
+    // npcs are about to move through. Returns false if there is no such cell.
+    fn act_cooperative<M>(
         &mut self,
         id: EntityId,
-        entity_store: &EntityStore,
+        coord: Coord,
         spatial_hash: &SpatialHashTable,
         config: PathfindingConfig,
         messages: &mut M,
-    ) where
+    ) -> bool
+    where
         M: PushMessages,
     {
-        let coord = entity_store
-            .coord
-            .get(&id)
-            .cloned()
-            .expect("Entity missing coord");
+        let player_coord = if let Some(player_coord) = self.distance_map_open_doors.origin() {
+            player_coord
+        } else {
+            return false;
+        };
+
+        self.update_flank_slots(player_coord, spatial_hash);
 
-        let cell = if let Some(cell) = self.distance_map.get(coord).cell() {
-            cell
+        let slot_index = {
+            let flank_slots = &self.flank_slots;
+            (0..flank_slots.len())
+                .filter(|&i| flank_slots[i].valid && !flank_slots[i].claimed)
+                .filter_map(|i| {
+                    flank_slots[i]
+                        .distance_map
+                        .cost(coord)
+                        .map(|cost| (i, cost))
+                })
+                .min_by_key(|&(_, cost)| cost)
+                .map(|(i, _)| i)
+        };
+        let slot_index = if let Some(slot_index) = slot_index {
+            slot_index
         } else {
-            // no path to player
-            return;
+            return false;
         };
+        let grid = ReservedCellGrid {
+            grid: SpatialHashSolidOrOccupiedCellGrid {
+                grid: spatial_hash,
+                open_doors: config.open_doors,
+            },
+            reserved: &self.reserved,
+        };
+        let distance_map = &self.flank_slots[slot_index].distance_map;
 
-        let current_cost = cell.cost();
+        self.best_step_seq += 1;
+        let direction = best_step(
+            &grid,
+            coord,
+            4, // max depth
+            TargetMap::Incremental(distance_map),
+            &mut self.best_step_queue,
+            &mut self.best_step_seen,
+            self.best_step_seq,
+        );
 
-        assert!(current_cost > 0, "Unexpected 0 cost distance cell");
+        let direction = if let Some(direction) = direction {
+            direction
+        } else {
+            return false;
+        };
+
+        let new = coord + direction.coord();
+        messages.change(insert::coord(id, new));
+
+        // reserve the step after this one so the next npc picks another route
+        let next = CardinalDirections
+            .into_iter()
+            .map(|direction| new + direction.coord())
+            .filter(|&next| grid.is_solid(next) == Some(false))
+            .filter_map(|next| distance_map.cost(next).map(|cost| (next, cost)))
+            .min_by_key(|&(_, cost)| cost);
+        self.reserved.insert(new);
+        if let Some((next, _)) = next {
+            self.reserved.insert(next);
+        }
+        // only claimed once an npc is actually heading for it
+        self.flank_slots[slot_index].claimed = true;
+
+        true
+    }
 
+    pub fn update_attractors<I>(&mut self, attractors: I, spatial_hash: &SpatialHashTable)
+    where
+        I: IntoIterator<Item = Attractor>,
+    {
         const CONFIG: SearchConfig = SearchConfig {
             allow_solid_start: true,
         };
 
-        let distance_map = if config.open_doors {
-            &self.distance_map_open_doors
-        } else {
-            &self.distance_map
+        self.next_attractors.clear();
+        self.next_attractors.extend(attractors);
+
+        let same_coords = self.next_attractors.len() == self.num_attractors
+            && self
+                .next_attractors
+                .iter()
+                .zip(self.attractor_maps.iter())
+                .all(|(attractor, attractor_map)| attractor.coord == attractor_map.attractor.coord);
+        if same_coords && !self.attractors_stale {
+            for (attractor, attractor_map) in self
+                .next_attractors
+                .iter()
+                .zip(self.attractor_maps.iter_mut())
+            {
+                attractor_map.attractor = *attractor;
+            }
+            return;
+        }
+        self.attractors_stale = false;
+
+        self.num_attractors = 0;
+        for &attractor in self.next_attractors.iter() {
+            if self.num_attractors == self.attractor_maps.len() {
+                self.attractor_maps.push(AttractorMap {
+                    attractor,
+                    distance_map: UniformDistanceMap::new(spatial_hash.size(), DirectionsCardinal),
+                });
+            }
+            let attractor_map = &mut self.attractor_maps[self.num_attractors];
+            attractor_map.attractor = attractor;
+            self.bfs
+                .populate_uniform_distance_map(
+                    &SpatialHashSolidCellGrid {
+                        grid: spatial_hash,
+                        open_doors: true,
+                    },
+                    attractor.coord,
+                    CONFIG,
+                    &mut attractor_map.distance_map,
+                )
+                .expect("Failed to update attractor distance map");
+            self.num_attractors += 1;
+        }
+    }
+
+    fn attractor_distance_map(
+        attractor_maps: &[AttractorMap],
+        coord: Coord,
+    ) -> Option<&UniformDistanceMap<u32, DirectionsCardinal>> {
+        attractor_maps
+            .iter()
+            .filter_map(|attractor_map| {
+                attractor_map
+                    .distance_map
+                    .cost(coord)
+                    .filter(|&cost| cost > 0 && cost <= attractor_map.attractor.range)
+                    .map(|cost| (attractor_map, cost))
+            })
+            .max_by(|&(a, cost_a), &(b, cost_b)| {
+                a.attractor
+                    .priority
+                    .cmp(&b.attractor.priority)
+                    .then(cost_b.cmp(&cost_a))
+            })
+            .map(|(attractor_map, _)| &attractor_map.distance_map)
+    }
+
+    fn step_towards<M>(
+        &mut self,
+        id: EntityId,
+        coord: Coord,
+        target: Coord,
+        spatial_hash: &SpatialHashTable,
+        config: PathfindingConfig,
+        messages: &mut M,
+    ) where
+        M: PushMessages,
+    {
+        const CONFIG: SearchConfig = SearchConfig {
+            allow_solid_start: true,
         };
 
-        let result = self.search.best_search_uniform_distance_map(
-            &SpatialHashSolidOrOccupiedCellGrid {
+        let result = self.search.astar_cardinal_manhatten_distance_heuristic(
+            &SpatialHashSolidCellGrid {
                 grid: spatial_hash,
                 open_doors: config.open_doors,
             },
             coord,
+            target,
             CONFIG,
-            4, // max depth
-            distance_map,
             &mut self.path,
         );
 
         match result {
             Ok(_) => {
                 if let Some(direction) = self.path.iter().next() {
-                    let delta = direction.coord();
-                    let new = coord + delta;
-                    messages.change(insert::coord(id, new));
+                    let new = coord + direction.coord();
+                    let occupied = spatial_hash
+                        .get(new)
+                        .map(|cell| !cell.npc_set.is_empty())
+                        .unwrap_or(true);
+                    if !occupied {
+                        messages.change(insert::coord(id, new));
+                    }
                 }
             }
             Err(Error::NoPath) => (),
@@ -149,6 +547,148 @@ impl PathfindingContext {
         }
     }
 
+    fn wander<M, R>(
+        id: EntityId,
+        coord: Coord,
+        spatial_hash: &SpatialHashTable,
+        config: PathfindingConfig,
+        rng: &mut R,
+        messages: &mut M,
+    ) where
+        M: PushMessages,
+        R: Rng,
+    {
+        let grid = SpatialHashSolidOrOccupiedCellGrid {
+            grid: spatial_hash,
+            open_doors: config.open_doors,
+        };
+        let directions = CardinalDirections
+            .into_iter()
+            .filter(|direction| grid.is_solid(coord + direction.coord()) == Some(false))
+            .collect::<Vec<_>>();
+        if let Some(direction) = directions.choose(rng) {
+            messages.change(insert::coord(id, coord + direction.coord()));
+        }
+    }
+
+    pub fn act<M, R>(
+        &mut self,
+        id: EntityId,
+        entity_store: &EntityStore,
+        spatial_hash: &SpatialHashTable,
+        target: NpcTarget,
+        rng: &mut R,
+        messages: &mut M,
+    ) where
+        M: PushMessages,
+        R: Rng,
+    {
+        let config = PathfindingConfig::for_entity(id, entity_store);
+        let coord = entity_store
+            .coord
+            .get(&id)
+            .cloned()
+            .expect("Entity missing coord");
+
+        let distance_map = if let Some(distance_map) =
+            Self::attractor_distance_map(&self.attractor_maps[0..self.num_attractors], coord)
+        {
+            TargetMap::Uniform(distance_map)
+        } else {
+            match target {
+                NpcTarget::Player => (),
+                NpcTarget::LastKnown(target) => {
+                    self.step_towards(id, coord, target, spatial_hash, config, messages);
+                    return;
+                }
+                NpcTarget::Wander => {
+                    Self::wander(id, coord, spatial_hash, config, rng, messages);
+                    return;
+                }
+            }
+
+            let current_cost = if let Some(cost) = self.player_map(config).cost(coord) {
+                cost
+            } else {
+                // no path to player
+                return;
+            };
+
+            assert!(current_cost > 0, "Unexpected 0 cost distance cell");
+
+            if config.cooperative
+                && current_cost > 1
+                && self.act_cooperative(id, coord, spatial_hash, config, messages)
+            {
+                return;
+            }
+
+            if config.open_doors {
+                TargetMap::Incremental(&self.distance_map_open_doors)
+            } else {
+                TargetMap::Incremental(&self.distance_map)
+            }
+        };
+
+        self.best_step_seq += 1;
+        let direction = best_step(
+            &SpatialHashSolidOrOccupiedCellGrid {
+                grid: spatial_hash,
+                open_doors: config.open_doors,
+            },
+            coord,
+            4, // max depth
+            distance_map,
+            &mut self.best_step_queue,
+            &mut self.best_step_seen,
+            self.best_step_seq,
+        );
+
+        if let Some(direction) = direction {
+            messages.change(insert::coord(id, coord + direction.coord()));
+        }
+    }
+
+    // Step to the free neighbouring cell that is furthest from the player
+    pub fn flee<M>(
+        &self,
+        id: EntityId,
+        entity_store: &EntityStore,
+        spatial_hash: &SpatialHashTable,
+        config: PathfindingConfig,
+        messages: &mut M,
+    ) where
+        M: PushMessages,
+    {
+        let coord = entity_store
+            .coord
+            .get(&id)
+            .cloned()
+            .expect("Entity missing coord");
+        let current_cost = if let Some(cost) = self.distance_to_player(coord) {
+            cost
+        } else {
+            return;
+        };
+        let grid = SpatialHashSolidOrOccupiedCellGrid {
+            grid: spatial_hash,
+            open_doors: config.open_doors,
+        };
+        let best = CardinalDirections
+            .into_iter()
+            .map(|direction| coord + direction.coord())
+            .filter(|&neighbour| grid.is_solid(neighbour) == Some(false))
+            .filter_map(|neighbour| {
+                self.distance_to_player(neighbour)
+                    .map(|cost| (neighbour, cost))
+            })
+            .filter(|&(_, cost)| cost > current_cost)
+            .max_by_key(|&(_, cost)| cost);
+        if let Some((neighbour, _)) = best {
+            messages.change(insert::coord(id, neighbour));
+        }
+    }
+
     pub fn sort_entities_by_distance_to_player(
         &self,
         entity_store: &EntityStore,
@@ -159,14 +699,14 @@ impl PathfindingContext {
         entities.sort_by(|a, b| {
             let coord_a = coord.get(a).expect("Entity missing coord");
             let coord_b = coord.get(b).expect("Entity missing coord");
-            if let Some(cell_a) = distance_map.get(*coord_a).cell() {
-                if let Some(cell_b) = distance_map.get(*coord_b).cell() {
-                    cell_a.cost().cmp(&cell_b.cost())
+            if let Some(cost_a) = distance_map.cost(*coord_a) {
+                if let Some(cost_b) = distance_map.cost(*coord_b) {
+                    cost_a.cmp(&cost_b)
                 } else {
                     Ordering::Less
                 }
             } else {
-                if distance_map.get(*coord_b).cell().is_some() {
+                if distance_map.cost(*coord_b).is_some() {
                     Ordering::Greater
                 } else {
                     Ordering::Equal
@@ -6,4 +6,6 @@ pub enum Pickup {
     Kevlar,
     MetabolAmmo,
     PushAmmo,
+    GrenadeAmmo,
+    FlareAmmo,
 }
@@ -1,15 +1,21 @@
 use alert::*;
 use beacon::*;
+use boss;
 use common_animations;
+use damage::{self, Damage, DamageType};
 use direction::CardinalDirection;
 use direction::*;
 use entity_store::*;
 use message_queues::PushMessages;
 use meter::Meter;
+use noise;
 use pickup::Pickup;
 use pushed::*;
 use rand::Rng;
+use stats::{DamageSource, StatEvent};
+use status::{self, StatusEffectType};
 use tile::*;
+use weapons;
 
 pub fn precheck<'a, I: IntoIterator<Item = &'a EntityChange>>(
     changes: I,
@@ -64,6 +70,64 @@ pub fn kevlar_blocks_attack<R: Rng>(
     None
 }
 
+// `direction` is the direction the attack is travelling in
+// Returns true if the attack got past the player's shield and armour
+fn attack_player<M, R>(
+    direction: CardinalDirection,
+    typ: DamageType,
+    entity_store: &EntityStore,
+    messages: &mut M,
+    rng: &mut R,
+) -> bool
+where
+    M: PushMessages,
+    R: Rng,
+{
+    let player_id = entity_store.player.iter().next().unwrap();
+
+    if entity_store.shield.get(player_id) == Some(&direction.opposite()) {
+        messages.alert(Alert::ShieldBlock);
+        return false;
+    }
+
+    let kevlar = if typ.blocked_by_kevlar() {
+        kevlar_blocks_attack(*player_id, entity_store, rng)
+    } else {
+        None
+    };
+
+    if let Some(mut kevlar) = kevlar {
+        kevlar.value -= 1;
+        messages.alert(Alert::ArmourBlock);
+        messages.change(insert::kevlar_meter(*player_id, kevlar));
+        false
+    } else {
+        let damage = Damage::new(1, typ, DamageSource::Npc);
+        damage::apply(*player_id, damage, entity_store, messages);
+        true
+    }
+}
+
+fn crush<M: PushMessages>(id: EntityId, entity_store: &EntityStore, messages: &mut M) {
+    // npcs are only pushed by the player
+    let damage = Damage::new(
+        weapons::CRUSH_DAMAGE,
+        DamageType::Kinetic,
+        DamageSource::Player,
+    );
+    if damage::apply(id, damage, entity_store, messages) > 0 {
+        common_animations::damage_flash(id, messages);
+    }
+    // the impact leaves them stunned, and no longer being pushed
+    status::clear(id, StatusEffectType::Pushed, messages);
+    status::apply(
+        id,
+        StatusEffectType::Stunned,
+        status::STUNNED_TURNS,
+        messages,
+    );
+}
+
 pub fn check<M, R>(
     change: &EntityChange,
     entity_store: &EntityStore,
@@ -214,16 +278,96 @@ where
                     }
                 }
 
+                if let Some(&range) = entity_store.grenade.get(&id) {
+                    if entity_store.slide_direction.contains_key(&id) {
+                        let blocked = sh_cell.solid_count > 0 || !sh_cell.npc_set.is_empty();
+                        if blocked || range == 0 {
+                            messages.change(remove::slide_direction(id));
+                            messages.change(insert::countdown(id, weapons::GRENADE_FUSE));
+                            if entity_store.coord.contains_key(&id) {
+                                return false;
+                            }
+                        } else {
+                            messages.change(insert::grenade(id, range - 1));
+                        }
+                    }
+                }
+
+                let explosion = entity_store.explosion.get(&id).cloned();
+
+                if let Some(explosion) = explosion.filter(|_| sh_cell.player_count > 0) {
+                    let player_id = entity_store.player.iter().next().unwrap();
+                    let damage = Damage::new(explosion.damage, DamageType::Blast, explosion.source);
+                    if damage::apply(*player_id, damage, entity_store, messages) > 0 {
+                        common_animations::damage_flash(*player_id, messages);
+                    }
+                    status::apply(
+                        *player_id,
+                        StatusEffectType::Burning,
+                        status::BURNING_TURNS,
+                        messages,
+                    );
+                }
+
+                if entity_store.spit.contains(&id) {
+                    if sh_cell.player_count > 0 {
+                        if let Some(&direction) = entity_store.slide_direction.get(&id) {
+                            let hit = attack_player(
+                                direction,
+                                DamageType::Chemical,
+                                entity_store,
+                                messages,
+                                rng,
+                            );
+                            if hit {
+                                let player_id = entity_store.player.iter().next().unwrap();
+                                status::apply(
+                                    *player_id,
+                                    StatusEffectType::Poisoned,
+                                    status::POISONED_TURNS,
+                                    messages,
+                                );
+                            }
+                        }
+                        messages.remove(id);
+                        return false;
+                    }
+                    if let Some(npc_id) = dest_npc {
+                        // spit hurts whichever npc gets in its way
+                        let damage = Damage::new(1, DamageType::Chemical, DamageSource::Npc);
+                        if damage::apply(*npc_id, damage, entity_store, messages) > 0 {
+                            common_animations::damage_flash(*npc_id, messages);
+                        }
+                        status::apply(
+                            *npc_id,
+                            StatusEffectType::Poisoned,
+                            status::POISONED_TURNS,
+                            messages,
+                        );
+                        messages.remove(id);
+                        return false;
+                    }
+                }
+
                 if let Some(npc_id) = dest_npc {
                     if entity_store.metabol_wave.contains_key(&id) {
+                        // the wave slows the metabolism of everything it hits
+                        status::apply(
+                            *npc_id,
+                            StatusEffectType::Slowed,
+                            status::SLOWED_TURNS,
+                            messages,
+                        );
                         if let Some(countdown) = entity_store.countdown.get(npc_id).cloned() {
-                            messages.change(insert::delayed_transform(*npc_id));
-                            messages.change(insert::countdown(*npc_id, countdown + 20));
-                            if let Some(mut tile_info) = entity_store.tile_info.get(npc_id).cloned()
-                            {
-                                tile_info.delayed_transform = true;
-                                messages.change(insert::tile_info(*npc_id, tile_info));
-                            }
+                            // the effect lasts until the delayed transform happens
+                            let countdown = countdown + status::TRANSFORM_DELAYED_TURNS;
+                            messages.change(insert::countdown(*npc_id, countdown));
+                            status::apply(
+                                *npc_id,
+                                StatusEffectType::TransformDelayed,
+                                countdown,
+                                messages,
+                            );
                         }
                     }
 
@@ -254,11 +398,12 @@ where
                                 range: 2,
                             };
                             messages.change(insert::pushed(*npc_id, pushed));
-                            if let Some(mut tile_info) = entity_store.tile_info.get(npc_id).cloned()
-                            {
-                                tile_info.pushed = true;
-                                messages.change(insert::tile_info(*npc_id, tile_info));
-                            }
+                            status::apply(
+                                *npc_id,
+                                StatusEffectType::Pushed,
+                                status::PUSHED_TURNS,
+                                messages,
+                            );
                         }
                     }
 
@@ -291,39 +436,66 @@ where
                         }
                     }
                     if entity_store.punch.contains(&id) {
-                        if let Some(mut health) = entity_store.health_meter.get(&npc_id).cloned() {
-                            health.value -= 1;
-                            messages.change(insert::health_meter(*npc_id, health));
+                        let damage = Damage::new(1, DamageType::Kinetic, DamageSource::Player);
+                        if damage::apply(*npc_id, damage, entity_store, messages) > 0 {
                             common_animations::damage_flash(*npc_id, messages);
                         }
                     }
                     if entity_store.bullet.contains_key(&id) {
-                        if let Some(mut health) = entity_store.health_meter.get(&npc_id).cloned() {
-                            health.value -= 1;
-                            messages.change(insert::health_meter(*npc_id, health));
+                        let damage = Damage::new(1, DamageType::Piercing, DamageSource::Player);
+                        if damage::apply(*npc_id, damage, entity_store, messages) > 0 {
                             common_animations::damage_flash(*npc_id, messages);
                         }
                         messages.remove(id);
                         return false;
                     }
+                    if let Some(explosion) = explosion {
+                        let damage =
+                            Damage::new(explosion.damage, DamageType::Blast, explosion.source);
+                        if damage::apply(*npc_id, damage, entity_store, messages) > 0 {
+                            common_animations::damage_flash(*npc_id, messages);
+                        }
+                        status::apply(
+                            *npc_id,
+                            StatusEffectType::Burning,
+                            status::BURNING_TURNS,
+                            messages,
+                        );
+                    }
                     if entity_store.rail_gun_shot.contains(&id) {
-                        if let Some(mut health) = entity_store.health_meter.get(&npc_id).cloned() {
-                            health.value -= 1;
-                            messages.change(insert::health_meter(*npc_id, health));
+                        messages.stat(StatEvent::RailGunHit);
+                        let damage = Damage::new(1, DamageType::Piercing, DamageSource::Player);
+                        if damage::apply(*npc_id, damage, entity_store, messages) > 0 {
                             common_animations::rail_gun_damage_flash(*npc_id, messages);
                         }
                     }
                 }
 
-                let door_cell = sh_cell.door_count > 0;
+                let door_cell = sh_cell.door_count > 0 && entity_store.door_opener.contains(&id);
 
                 let solid_cell =
                     (sh_cell.solid_count > 0 && !door_cell) || sh_cell.npc_set.len() > 0;
 
+                if solid_cell && entity_store.pushed.contains_key(&id) {
+                    // npcs pushed into something solid are hurt by the impact, as
+                    // is any npc they are pushed into
+                    crush(id, entity_store, messages);
+                    if let Some(npc_id) = dest_npc {
+                        crush(*npc_id, entity_store, messages);
+                    }
+                    messages.change(remove::pushed(id));
+                    messages.alert(Alert::Crushed);
+                    return false;
+                }
+
                 if solid_cell && entity_store.collider.contains(&id) {
                     return false;
                 }
 
+                if door_cell {
+                    messages.noise(coord, noise::DOOR_NOISE_RADIUS);
+                }
+
                 if let Some(&range) = entity_store.bullet.get(&id) {
                     if sh_cell.solid_count > 0 || range == 0 {
                         messages.remove(id);
@@ -339,6 +511,24 @@ where
                     return false;
                 }
 
+                if is_npc {
+                    if let Some(decoy_id) = sh_cell.decoy_set.iter().next() {
+                        let npc_coord = entity_store
+                            .coord
+                            .get(&id)
+                            .cloned()
+                            .expect("NPC missing coord");
+                        let direction = CardinalDirection::from_unit_coord(coord - npc_coord);
+                        let punch_id = id_allocator.allocate();
+                        common_animations::punch(punch_id, coord, direction, messages);
+                        let damage = Damage::new(1, DamageType::Kinetic, DamageSource::Npc);
+                        if damage::apply(*decoy_id, damage, entity_store, messages) > 0 {
+                            common_animations::damage_flash(*decoy_id, messages);
+                        }
+                        return false;
+                    }
+                }
+
                 if sh_cell.player_count > 0 && is_npc {
                     let npc_coord = entity_store
                         .coord
@@ -353,22 +543,7 @@ where
 
                     common_animations::punch(punch_id, coord, direction, messages);
 
-                    let player_id = entity_store.player.iter().next().unwrap();
-
-                    let mut health = entity_store.health_meter.get(player_id).cloned().unwrap();
-
-                    let change = if let Some(mut kevlar) =
-                        kevlar_blocks_attack(*player_id, entity_store, rng)
-                    {
-                        kevlar.value -= 1;
-                        messages.alert(Alert::ArmourBlock);
-                        insert::kevlar_meter(*player_id, kevlar)
-                    } else {
-                        health.value -= 1;
-                        insert::health_meter(*player_id, health)
-                    };
-
-                    messages.change(change);
+                    attack_player(direction, DamageType::Kinetic, entity_store, messages, rng);
 
                     return false;
                 };
@@ -387,6 +562,7 @@ where
                             let mut info = info;
                             info.active = true;
                             messages.change(insert::npc(id, info));
+                            messages.change(insert::remembered_target(id, coord));
                         }
                         return false;
                     } else {
@@ -428,6 +604,24 @@ where
                                         messages.remove(*pickup_id);
                                     }
                                 }
+                                Pickup::GrenadeAmmo => {
+                                    if let Some(mut ammo) =
+                                        entity_store.grenade_meter.get(&id).cloned()
+                                    {
+                                        ammo.value = ammo.max;
+                                        messages.change(insert::grenade_meter(id, ammo));
+                                        messages.remove(*pickup_id);
+                                    }
+                                }
+                                Pickup::FlareAmmo => {
+                                    if let Some(mut ammo) =
+                                        entity_store.flare_meter.get(&id).cloned()
+                                    {
+                                        ammo.value = ammo.max;
+                                        messages.change(insert::flare_meter(id, ammo));
+                                        messages.remove(*pickup_id);
+                                    }
+                                }
                                 Pickup::Health => {
                                     if let Some(mut health) =
                                         entity_store.health_meter.get(&id).cloned()
@@ -448,6 +642,12 @@ where
                                 }
                             }
                         }
+                        if let Some(direction) = entity_store.shield.get(&id) {
+                            for &overlay_id in entity_store.shield_overlay.iter() {
+                                messages
+                                    .change(insert::coord(overlay_id, coord + direction.coord()));
+                            }
+                        }
                         messages.move_player(coord);
                     }
                 }
@@ -459,12 +659,79 @@ where
                 messages.change(insert::tile_info(id, tile_info));
             }
 
+            if let Some(&current_phase) = entity_store.queen_phase.get(&id) {
+                let phase = boss::queen_phase(health.value);
+                if phase > current_phase && health.value > 0 {
+                    for phase in (current_phase + 1)..(phase + 1) {
+                        boss::enter_queen_phase(
+                            id,
+                            phase,
+                            entity_store,
+                            spatial_hash,
+                            id_allocator,
+                            messages,
+                            rng,
+                        );
+                    }
+                    messages.change(insert::queen_phase(id, phase));
+                }
+            }
+
             if health.value == 0 {
                 if entity_store.player.contains(&id) {
                     messages.lose();
                 } else {
                     messages.remove(id);
                 }
+                let already_dead = entity_store
+                    .health_meter
+                    .get(&id)
+                    .map(|health| health.value <= 0)
+                    .unwrap_or(true);
+                // whoever killed an npc is also responsible for anything its
+                // death causes
+                let source = entity_store
+                    .damaged_by
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or(DamageSource::Npc);
+                if entity_store.npc.contains_key(&id) && !already_dead {
+                    messages.stat(StatEvent::Kill(source));
+                }
+                if entity_store.volatile.contains(&id) && !already_dead {
+                    if let Some(&coord) = entity_store.coord.get(&id) {
+                        common_animations::area_damage(
+                            coord,
+                            weapons::VOLATILE_EGG_EXPLOSION,
+                            source,
+                            spatial_hash,
+                            id_allocator,
+                            messages,
+                        );
+                    }
+                }
+            }
+        }
+        &EntityChange::Insert(id, ComponentValue::ShieldMeter(shield))
+            if shield.value == 0 && entity_store.shield.contains_key(&id) =>
+        {
+            messages.change(remove::shield(id));
+            for &overlay_id in entity_store.shield_overlay.iter() {
+                messages.remove(overlay_id);
+            }
+        }
+        &EntityChange::Insert(id, ComponentValue::RegenerationMeter(regeneration))
+            if regeneration.value >= regeneration.max =>
+        {
+            if let Some(mut health) = entity_store.health_meter.get(&id).cloned() {
+                if health.value < health.max {
+                    health.value += 1;
+                    messages.change(insert::health_meter(id, health));
+                    messages.change(insert::regeneration_meter(
+                        id,
+                        Meter::empty(regeneration.max),
+                    ));
+                }
             }
         }
         &EntityChange::Insert(id, ComponentValue::StaminaTick(value)) => {
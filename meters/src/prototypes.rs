@@ -7,10 +7,16 @@ use tile_info::TileInfo;
 use message_queues::PushMessages;
 use meter::*;
 use pickup::Pickup;
+use scheduler;
 use npc_info::*;
 use transform::*;
 use beacon::*;
+use boss;
+use damage::*;
+use light::{self, Light};
+use status::StatusEffectSet;
 use wave::*;
+use weapons::{self, ExplosionInfo};
 
 const FLOOR_DEPTH: i32 = 1;
 const WALL_DEPTH: i32 = 2;
@@ -28,6 +34,7 @@ pub enum Prototype {
     RailGunShot(EntityId, Coord, CardinalDirection),
     MetabolWave(EntityId, Coord, bool, bool, bool, CardinalDirection, i32),
     PushWave(EntityId, Coord, bool, bool, bool, CardinalDirection, i32),
+    Explosion(EntityId, Coord, ExplosionInfo),
 }
 
 impl Prototype {
@@ -49,6 +56,10 @@ impl Prototype {
                 push_wave(id, coord, leader, left, right, direction, range, messages);
                 id
             }
+            Prototype::Explosion(id, coord, info) => {
+                explosion(id, coord, info, messages);
+                id
+            }
         }
     }
 }
@@ -57,6 +68,8 @@ pub fn player<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
     messages.change(insert::coord(id, coord));
     messages.change(insert::player(id));
     messages.change(insert::door_opener(id));
+    messages.change(insert::speed(id, scheduler::NORMAL_SPEED));
+    messages.change(insert::light(id, light::PLAYER_LIGHT));
     messages.change(insert::collider(id));
     let health = Meter::full(MeterType::Health.player_max());
     messages.change(insert::tile_info(
@@ -129,15 +142,19 @@ pub fn larvae<M: PushMessages, R: Rng>(id: EntityId, coord: Coord, messages: &mu
             boss: false,
             mobile: true,
             active: false,
-            fast: false,
         },
     ));
+    messages.change(insert::speed(id, scheduler::SLOW_SPEED));
+    // larvae hatched from volatile eggs aren't volatile themselves
+    messages.change(remove::volatile(id));
+    messages.change(remove::light(id));
     let health = Meter::full(2);
     messages.change(insert::tile_info(
         id,
         TileInfo::with_health(Tile::Larvae, NPC_DEPTH, health),
     ));
     messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(id, Resistances::new().with(DamageType::Chemical, 1)));
     messages.change(insert::countdown(id, 30 + rng.gen::<i32>().abs() % 40));
     messages.change(insert::transform(id, Transform::Chrysalis));
     messages.change(insert::collider(id));
@@ -151,16 +168,24 @@ pub fn beetoid<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
             boss: false,
             mobile: true,
             active: false,
-            fast: false,
         },
     ));
+    messages.change(insert::speed(id, scheduler::NORMAL_SPEED));
     let health = Meter::full(3);
     messages.change(insert::tile_info(
         id,
         TileInfo::with_health(Tile::Beetoid, NPC_DEPTH, health),
     ));
     messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(
+        id,
+        Resistances::new()
+            .with(DamageType::Chemical, -1)
+            .with(DamageType::Blast, 1),
+    ));
     messages.change(insert::collider(id));
+    messages.change(insert::door_opener(id));
+    messages.change(insert::cooperative(id));
 }
 
 pub fn aracnoid<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
@@ -171,15 +196,40 @@ pub fn aracnoid<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
             boss: false,
             mobile: true,
             active: false,
-            fast: true,
         },
     ));
+    messages.change(insert::speed(id, scheduler::FAST_SPEED));
     let health = Meter::full(2);
     messages.change(insert::tile_info(
         id,
         TileInfo::with_health(Tile::Aracnoid, NPC_DEPTH, health),
     ));
     messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(id, Resistances::new().with(DamageType::Kinetic, 1)));
+    messages.change(insert::collider(id));
+    messages.change(insert::door_opener(id));
+    messages.change(insert::cooperative(id));
+}
+
+pub fn spitter<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::npc(
+        id,
+        NpcInfo {
+            boss: false,
+            mobile: true,
+            active: false,
+        },
+    ));
+    messages.change(insert::speed(id, scheduler::NORMAL_SPEED));
+    let health = Meter::full(2);
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::with_health(Tile::Spitter, NPC_DEPTH, health),
+    ));
+    messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(id, Resistances::new().with(DamageType::Chemical, -1)));
+    messages.change(insert::spitter(id));
     messages.change(insert::collider(id));
 }
 
@@ -196,9 +246,11 @@ pub fn chrysalis<M: PushMessages, R: Rng>(
             boss: false,
             mobile: false,
             active: false,
-            fast: false,
         },
     ));
+    // chrysalises are transformed larvae, which can move and have resistances
+    messages.change(remove::speed(id));
+    messages.change(remove::resistances(id));
     let health = Meter::full(1);
     messages.change(insert::tile_info(
         id,
@@ -222,7 +274,6 @@ pub fn egg<M: PushMessages, R: Rng>(id: EntityId, coord: Coord, messages: &mut M
             boss: false,
             mobile: false,
             active: false,
-            fast: false,
         },
     ));
     let health = Meter::full(3);
@@ -231,9 +282,40 @@ pub fn egg<M: PushMessages, R: Rng>(id: EntityId, coord: Coord, messages: &mut M
         TileInfo::with_health(Tile::Egg, NPC_DEPTH, health),
     ));
     messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(id, Resistances::new().with(DamageType::Blast, 1)));
+    messages.change(insert::countdown(id, 12 + rng.gen::<i32>().abs() % 30));
+    messages.change(insert::transform(id, Transform::Larvae));
+    messages.change(insert::light(id, light::EGG_LIGHT));
+    messages.change(insert::collider(id));
+}
+
+pub fn volatile_egg<M: PushMessages, R: Rng>(
+    id: EntityId,
+    coord: Coord,
+    messages: &mut M,
+    rng: &mut R,
+) {
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::npc(
+        id,
+        NpcInfo {
+            boss: false,
+            mobile: false,
+            active: false,
+        },
+    ));
+    let health = Meter::full(2);
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::with_health(Tile::VolatileEgg, NPC_DEPTH, health),
+    ));
+    messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(id, Resistances::new().with(DamageType::Blast, 1)));
     messages.change(insert::countdown(id, 12 + rng.gen::<i32>().abs() % 30));
     messages.change(insert::transform(id, Transform::Larvae));
+    messages.change(insert::light(id, light::EGG_LIGHT));
     messages.change(insert::collider(id));
+    messages.change(insert::volatile(id));
 }
 
 pub fn super_egg<M: PushMessages, R: Rng>(
@@ -249,7 +331,6 @@ pub fn super_egg<M: PushMessages, R: Rng>(
             boss: false,
             mobile: false,
             active: false,
-            fast: false,
         },
     ));
     let health = Meter::full(8);
@@ -262,6 +343,7 @@ pub fn super_egg<M: PushMessages, R: Rng>(
     messages.change(insert::countdown(id, max));
     messages.change(insert::countdown_max(id, max));
     messages.change(insert::transform(id, Transform::Queen));
+    messages.change(insert::light(id, light::SUPER_EGG_LIGHT));
     messages.change(insert::collider(id));
 }
 
@@ -273,10 +355,16 @@ pub fn queen<M: PushMessages>(id: EntityId, coord: Coord, boss: bool, messages:
             boss,
             active: boss,
             mobile: true,
-            fast: false,
         },
     ));
-    let health = Meter::full(10);
+    messages.change(insert::speed(id, scheduler::NORMAL_SPEED));
+    // queens hatched from super eggs stop glowing
+    messages.change(remove::light(id));
+    let health = if boss {
+        Meter::full(boss::QUEEN_BOSS_HEALTH)
+    } else {
+        Meter::full(10)
+    };
     messages.change(insert::tile_info(
         id,
         TileInfo {
@@ -286,11 +374,17 @@ pub fn queen<M: PushMessages>(id: EntityId, coord: Coord, boss: bool, messages:
             boss,
             health_meter: Some(health),
             countdown: None,
-            delayed_transform: false,
-            pushed: false,
+            status_effects: StatusEffectSet::default(),
+            transform: None,
         },
     ));
     messages.change(insert::health_meter(id, health));
+    messages.change(insert::resistances(id, Resistances::new().with(DamageType::Chemical, -1)));
+    messages.change(insert::door_opener(id));
+    if boss {
+        messages.change(insert::queen_phase(id, 0));
+        messages.change(insert::nest(id, coord));
+    }
 }
 
 pub fn stairs<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
@@ -327,6 +421,104 @@ pub fn bullet<M: PushMessages>(
     ));
 }
 
+pub fn spit<M: PushMessages>(
+    id: EntityId,
+    coord: Coord,
+    direction: CardinalDirection,
+    range: u32,
+    messages: &mut M,
+) {
+    messages.change(insert::slide_direction(id, direction));
+    messages.change(insert::bullet(id, range));
+    messages.change(insert::spit(id));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::Spit, BULLET_DEPTH),
+    ));
+}
+
+pub fn grenade<M: PushMessages>(
+    id: EntityId,
+    coord: Coord,
+    direction: CardinalDirection,
+    range: u32,
+    messages: &mut M,
+) {
+    messages.change(insert::slide_direction(id, direction));
+    messages.change(insert::grenade(id, range));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::Grenade, BULLET_DEPTH),
+    ));
+}
+
+pub fn explosion<M: PushMessages>(
+    id: EntityId,
+    coord: Coord,
+    info: ExplosionInfo,
+    messages: &mut M,
+) {
+    messages.change(insert::explosion(id, info));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::Explosion, ANIMATION_DEPTH),
+    ));
+}
+
+pub fn flare<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
+    messages.change(insert::flare(id));
+    messages.change(insert::countdown(id, weapons::FLARE_TURNS));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::Flare, PICKUP_DEPTH),
+    ));
+}
+
+pub fn room_light<M: PushMessages>(id: EntityId, coord: Coord, radius: u32, messages: &mut M) {
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::light(
+        id,
+        Light::new(radius, light::ROOM_LIGHT_INTENSITY),
+    ));
+}
+
+pub fn muzzle_flash<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
+    messages.change(insert::muzzle_flash(id));
+    messages.change(insert::countdown(id, 0));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::light(id, light::MUZZLE_FLASH_LIGHT));
+}
+
+pub fn decoy<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
+    let health = Meter::full(weapons::DECOY_HEALTH);
+    messages.change(insert::decoy(id));
+    messages.change(insert::health_meter(id, health));
+    messages.change(insert::countdown(id, weapons::DECOY_TURNS));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::with_health(Tile::Decoy, NPC_DEPTH, health),
+    ));
+}
+
+pub fn shield_overlay<M: PushMessages>(
+    id: EntityId,
+    coord: Coord,
+    direction: CardinalDirection,
+    messages: &mut M,
+) {
+    messages.change(insert::shield_overlay(id));
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::Shield(direction), BULLET_DEPTH),
+    ));
+}
+
 pub fn rail_gun_shot<M: PushMessages>(
     id: EntityId,
     coord: Coord,
@@ -390,6 +582,24 @@ pub fn push_ammo_pickup<M: PushMessages>(id: EntityId, coord: Coord, messages: &
     ));
 }
 
+pub fn grenade_ammo_pickup<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::pickup(id, Pickup::GrenadeAmmo));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::GrenadeAmmoPickup, PICKUP_DEPTH),
+    ));
+}
+
+pub fn flare_ammo_pickup<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
+    messages.change(insert::coord(id, coord));
+    messages.change(insert::pickup(id, Pickup::FlareAmmo));
+    messages.change(insert::tile_info(
+        id,
+        TileInfo::new(Tile::FlareAmmoPickup, PICKUP_DEPTH),
+    ));
+}
+
 pub fn kevlar_pickup<M: PushMessages>(id: EntityId, coord: Coord, messages: &mut M) {
     messages.change(insert::coord(id, coord));
     messages.change(insert::pickup(id, Pickup::Kevlar));
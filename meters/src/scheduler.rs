@@ -0,0 +1,74 @@
+use entity_store::*;
+use status::StatusEffectType;
+use std::cmp;
+use std::collections::BTreeMap;
+
+pub const NORMAL_SPEED: u32 = 10;
+pub const FAST_SPEED: u32 = 20;
+pub const SLOW_SPEED: u32 = 5;
+
+// energy spent by an actor each time it takes a turn
+pub const TURN_ENERGY: i32 = 10;
+
+// Every entity with a speed is given energy in proportion to its speed at the
+// start of each round, and may take a turn whenever it has enough energy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scheduler {
+    energy: BTreeMap<EntityId, i32>,
+}
+
+pub fn speed(id: EntityId, entity_store: &EntityStore) -> u32 {
+    let speed = entity_store.speed.get(&id).cloned().unwrap_or(0);
+    let status_effects = entity_store
+        .status_effects
+        .get(&id)
+        .cloned()
+        .unwrap_or_default();
+    let speed = if status_effects.contains(StatusEffectType::Slowed) {
+        speed / 2
+    } else {
+        speed
+    };
+    if status_effects.contains(StatusEffectType::Hasted) {
+        speed * 2
+    } else {
+        speed
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            energy: BTreeMap::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.energy.clear();
+    }
+
+    pub fn start_round(&mut self, entity_store: &EntityStore) {
+        // forget about entities which no longer exist, as their ids may be reused
+        self.energy
+            .retain(|id, _| entity_store.speed.contains_key(id));
+        for &id in entity_store.speed.keys() {
+            let speed = speed(id, entity_store) as i32;
+            let energy = self.energy.entry(id).or_insert(0);
+            // energy that wasn't spent last round (e.g. by sleeping npcs) is lost
+            *energy = cmp::min(*energy + speed, cmp::max(speed, TURN_ENERGY));
+        }
+    }
+
+    pub fn is_ready(&self, id: EntityId) -> bool {
+        self.energy
+            .get(&id)
+            .map(|&energy| energy >= TURN_ENERGY)
+            .unwrap_or(false)
+    }
+
+    pub fn spend(&mut self, id: EntityId) {
+        if let Some(energy) = self.energy.get_mut(&id) {
+            *energy -= TURN_ENERGY;
+        }
+    }
+}
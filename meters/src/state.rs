@@ -3,6 +3,7 @@ use animation::*;
 use best::*;
 use change::ChangeContext;
 use common_animations;
+use damage::{self, Damage, DamageType, Hit};
 use direction::*;
 use entity_store::*;
 use event::*;
@@ -10,9 +11,13 @@ use goal::*;
 use grid_2d;
 use grid_2d::Size;
 use grid_2d::*;
+use grid_search::*;
 use input::*;
+use light::{self, LightContext};
+use loadout::*;
 use message_queues::*;
 use meter::*;
+use noise::*;
 use npc_info::*;
 use pathfinding::*;
 use policy;
@@ -20,23 +25,34 @@ use prototypes;
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::{Rng, SeedableRng};
+use scheduler::Scheduler;
 use shadowcast::{self, ShadowcastContext};
-use std::collections::HashSet;
-use std::iter::Enumerate;
+use stats::{DamageSource, StatEvent, Stats};
+use status::{self, StatusEffectType, StatusEffects};
+use std::collections::{vec_deque, HashSet, VecDeque};
 use std::slice;
 use std::time::Duration;
 use terrain::*;
+use tile::*;
 use tile_info::*;
 use transform::*;
 use weapons;
 use world::World;
 
 const NUM_LEVELS: usize = 6;
+const COMBAT_LOG_LENGTH: usize = 8;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 struct VisibilityCell {
     tiles: Vec<TileInfo>,
     last_updated: u64,
+    // cells in line of sight of the player are only seen if they are lit,
+    // but npcs in the dark can still see the player
+    last_in_sight: u64,
+    light: u8,
+    // the npc last seen in this cell, which is still drawn after the cell
+    // goes out of view
+    npc: Option<TileInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,7 +70,7 @@ pub enum Visibility {
 }
 
 impl<'a> Iterator for VisibilityIter<'a> {
-    type Item = (slice::Iter<'a, TileInfo>, Coord, Visibility);
+    type Item = (slice::Iter<'a, TileInfo>, Coord, Visibility, u8);
     fn next(&mut self) -> Option<Self::Item> {
         while let Some((coord, cell)) = self.iter.next() {
             let visibility = if cell.last_updated == 0 {
@@ -64,7 +80,27 @@ impl<'a> Iterator for VisibilityIter<'a> {
             } else {
                 Visibility::Remembered
             };
-            return Some((cell.tiles.iter(), coord, visibility));
+            return Some((cell.tiles.iter(), coord, visibility, cell.light));
+        }
+        None
+    }
+}
+
+pub struct RememberedNpcIter<'a> {
+    iter: grid_2d::GridEnumerate<'a, VisibilityCell>,
+    time: u64,
+}
+
+impl<'a> Iterator for RememberedNpcIter<'a> {
+    type Item = (Coord, TileInfo);
+    fn next(&mut self) -> Option<Self::Item> {
+        for (coord, cell) in self.iter.by_ref() {
+            if cell.last_updated == 0 || cell.last_updated == self.time {
+                continue;
+            }
+            if let Some(npc) = cell.npc {
+                return Some((coord, npc));
+            }
         }
         None
     }
@@ -80,51 +116,230 @@ impl VisibilityGrid {
             time,
         }
     }
+    fn remembered_npcs(&self, time: u64) -> RememberedNpcIter<'_> {
+        RememberedNpcIter {
+            iter: self.0.enumerate(),
+            time,
+        }
+    }
     fn clear(&mut self) {
         for cell in self.0.iter_mut() {
             cell.last_updated = 0;
+            cell.last_in_sight = 0;
+            cell.light = 0;
+            cell.npc = None;
             cell.tiles.clear();
         }
     }
     fn get(&self, coord: Coord) -> Option<&VisibilityCell> {
         self.0.get(coord)
     }
+    fn travel_path(
+        &self,
+        bfs: &mut BfsContext,
+        time: u64,
+        start: Coord,
+        destination: Coord,
+        path: &mut Vec<Direction>,
+    ) -> bool {
+        const CONFIG: SearchConfig = SearchConfig {
+            allow_solid_start: true,
+        };
+        let grid = KnownPassableGrid {
+            grid: self,
+            time,
+            destination: Some(destination),
+        };
+        bfs.bfs(&grid, start, destination, DirectionsCardinal, CONFIG, path)
+            .is_ok()
+    }
+    fn explore_path(
+        &self,
+        bfs: &mut BfsContext,
+        time: u64,
+        start: Coord,
+        visited: &HashSet<Coord>,
+        path: &mut Vec<Direction>,
+    ) -> bool {
+        const CONFIG: SearchConfig = SearchConfig {
+            allow_solid_start: true,
+        };
+        let grid = KnownPassableGrid {
+            grid: self,
+            time,
+            destination: None,
+        };
+        let is_unexplored = |coord: Coord| {
+            let cell = if let Some(cell) = self.get(coord) {
+                cell
+            } else {
+                return false;
+            };
+            if !visited.contains(&coord)
+                && cell
+                    .tiles
+                    .iter()
+                    .any(|tile_info| tile_info.tile.is_pickup())
+            {
+                return true;
+            }
+            CardinalDirections.into_iter().any(|direction| {
+                self.get(coord + direction.coord())
+                    .map(|cell| cell.last_updated == 0)
+                    .unwrap_or(false)
+            })
+        };
+        let result = bfs.bfs_predicate(
+            &grid,
+            start,
+            is_unexplored,
+            DirectionsCardinal,
+            CONFIG,
+            path,
+        );
+        result.is_ok() && !path.is_empty()
+    }
 }
 
 struct VisibilityRefs<'a> {
     grid: &'a mut VisibilityGrid,
     world: &'a World,
+    light: &'a LightContext,
+}
+
+fn see_cell(coord: Coord, time: u64, light: u8, refs: &mut VisibilityRefs) {
+    if let Some(cell) = refs.grid.0.get_mut(coord) {
+        if let Some(sh_cell) = refs.world.spatial_hash.get(coord) {
+            if sh_cell.last_updated > cell.last_updated {
+                cell.tiles.clear();
+                cell.npc = None;
+                for id in sh_cell.tile_set.iter() {
+                    if let Some(&tile_info) = refs.world.entity_store.tile_info.get(&id) {
+                        let mut tile_info = tile_info;
+                        if let Some(status_effects) = refs.world.entity_store.status_effects.get(id)
+                        {
+                            tile_info.status_effects = status_effects.set();
+                        }
+                        tile_info.transform = refs.world.entity_store.transform.get(id).cloned();
+                        if refs.world.entity_store.npc.contains_key(id) {
+                            cell.npc = Some(tile_info);
+                        }
+                        cell.tiles.push(tile_info);
+                    }
+                }
+            }
+            cell.last_updated = time;
+            cell.last_in_sight = time;
+            cell.light = light;
+        }
+    }
 }
 
 fn for_each_visible_cell(
     player_coord: Coord,
     time: u64,
+    vision_radius: u32,
     refs: &mut VisibilityRefs,
     ctx: &mut ShadowcastContext<u8>,
 ) {
     ctx.for_each(
         player_coord,
         &refs.world.spatial_hash,
-        shadowcast::vision_distance::Square::new(128),
+        shadowcast::vision_distance::Circle::new(vision_radius),
         1,
         |coord, _, _| {
-            if let Some(cell) = refs.grid.0.get_mut(coord) {
-                if let Some(sh_cell) = refs.world.spatial_hash.get(coord) {
-                    if sh_cell.last_updated > cell.last_updated {
-                        cell.tiles.clear();
-                        for id in sh_cell.tile_set.iter() {
-                            if let Some(&tile_info) = refs.world.entity_store.tile_info.get(&id) {
-                                cell.tiles.push(tile_info);
-                            }
-                        }
-                    }
-                    cell.last_updated = time;
-                }
+            let light = refs.light.get(coord);
+            if light > 0 {
+                see_cell(coord, time, light, refs);
+            } else if let Some(cell) = refs.grid.0.get_mut(coord) {
+                cell.last_in_sight = time;
             }
         },
     );
 }
 
+// flares light up everything around them, regardless of walls
+fn for_each_flare_lit_cell(time: u64, refs: &mut VisibilityRefs) {
+    let radius = weapons::FLARE_RADIUS;
+    let flare_coords = refs
+        .world
+        .entity_store
+        .flare
+        .iter()
+        .filter_map(|id| refs.world.entity_store.coord.get(id).cloned())
+        .collect::<Vec<_>>();
+    for flare_coord in flare_coords {
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                if x * x + y * y <= radius * radius {
+                    let coord = flare_coord + Coord::new(x, y);
+                    see_cell(coord, time, light::MAX_INTENSITY, refs);
+                }
+            }
+        }
+    }
+}
+
+// Sleeping npcs which can see the player roll against the player's stealth
+// meter to notice them. The meter drains by one in each phase where any npc
+// was evaded.
+struct StealthRolls {
+    stealth: Option<Meter>,
+    evaded: bool,
+}
+
+impl StealthRolls {
+    fn new(player_id: EntityId, entity_store: &EntityStore) -> Self {
+        Self {
+            stealth: entity_store.stealth_meter.get(&player_id).cloned(),
+            evaded: false,
+        }
+    }
+    fn noticed<R: Rng>(&mut self, in_sight: bool, rng: &mut R) -> bool {
+        if !in_sight {
+            return false;
+        }
+        let hidden = self
+            .stealth
+            .map(|stealth| rng.gen_range(0, stealth.max) < stealth.value)
+            .unwrap_or(false);
+        self.evaded = self.evaded || hidden;
+        !hidden
+    }
+    fn spend<M: PushMessages>(self, player_id: EntityId, messages: &mut M) {
+        if self.evaded {
+            if let Some(mut stealth) = self.stealth {
+                stealth.value -= 1;
+                messages.change(insert::stealth_meter(player_id, stealth));
+            }
+        }
+    }
+}
+
+// The player only travels through cells they remember being passable, and
+// around npcs they can see. Stairs and exits are avoided unless they are the
+// destination.
+struct KnownPassableGrid<'a> {
+    grid: &'a VisibilityGrid,
+    time: u64,
+    destination: Option<Coord>,
+}
+
+impl<'a> SolidGrid for KnownPassableGrid<'a> {
+    fn is_solid(&self, coord: Coord) -> Option<bool> {
+        let destination = self.destination;
+        self.grid.get(coord).map(|cell| {
+            cell.last_updated == 0
+                || (cell.last_updated == self.time && cell.npc.is_some())
+                || cell.tiles.iter().any(|tile_info| match tile_info.tile {
+                    Tile::Wall | Tile::CavernWall => true,
+                    Tile::Stairs | Tile::Exit => Some(coord) != destination,
+                    _ => false,
+                })
+        })
+    }
+}
+
 impl shadowcast::InputGrid for SpatialHashTable {
     type Opacity = u8;
     fn size(&self) -> Size {
@@ -141,7 +356,7 @@ impl shadowcast::InputGrid for SpatialHashTable {
 enum TurnState {
     Player,
     Npcs,
-    FastNpcs,
+    ReadyNpcs,
 }
 
 #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
@@ -157,6 +372,15 @@ struct PlayerTurnEventEntry {
     reset: u32,
 }
 
+impl PlayerTurnEvent {
+    fn meter_type(self) -> MeterType {
+        match self {
+            PlayerTurnEvent::ChangeActiveMeter(typ, _) => typ.into(),
+            PlayerTurnEvent::ChangePassiveMeter(typ, _) => typ.into(),
+        }
+    }
+}
+
 impl PlayerTurnEventEntry {
     fn full(event: PlayerTurnEvent, reset: u32) -> Self {
         Self {
@@ -170,20 +394,20 @@ impl PlayerTurnEventEntry {
 pub struct ActiveMeterInfoIter<'a> {
     entity_store: &'a EntityStore,
     entity_id: EntityId,
-    meter_metadata: Enumerate<slice::Iter<'a, ActiveMeterType>>,
+    meter_metadata: LoadoutMeterIter<'a>,
     selected_meter: Option<ActiveMeterType>,
 }
 
 impl<'a> Iterator for ActiveMeterInfoIter<'a> {
     type Item = ActiveMeterInfo;
     fn next(&mut self) -> Option<Self::Item> {
-        self.meter_metadata.next().map(|(index, &typ)| {
+        self.meter_metadata.next().map(|(identifier, typ)| {
             let general_typ: MeterType = typ.into();
             let meter = Meter::from_entity_store(self.entity_id, self.entity_store, general_typ)
                 .expect("Meter identifiers out of sync with game state");
             ActiveMeterInfo {
                 typ,
-                identifier: ActiveMeterIdentifier::from_index(index),
+                identifier,
                 meter,
                 is_selected: Some(typ) == self.selected_meter,
             }
@@ -224,9 +448,10 @@ pub struct State {
     rng: StdRng,
     player_id: EntityId,
     turn: TurnState,
+    scheduler: Scheduler,
     pathfinding: PathfindingContext,
     change_context: ChangeContext,
-    active_meters: Vec<ActiveMeterType>,
+    active_meters: Loadout,
     passive_meters: Vec<PassiveMeterType>,
     selected_meter: Option<ActiveMeterType>,
     levels: Vec<TerrainInfo>,
@@ -234,7 +459,16 @@ pub struct State {
     player_turn_events: Vec<PlayerTurnEventEntry>,
     shadowcast: ShadowcastContext<u8>,
     visibility_grid: VisibilityGrid,
+    vision_radius: u32,
+    light: LightContext,
+    noise: NoiseContext,
+    bfs: BfsContext,
     rng_seed: usize,
+    stats: Stats,
+    combat_log: VecDeque<Hit>,
+    // cells the player has stood on this level, so exploring can skip over
+    // pickups they chose to leave behind
+    visited: HashSet<Coord>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -244,13 +478,18 @@ pub struct SaveState {
     next_rng_seed: usize,
     size: Size,
     turn: TurnState,
+    scheduler: Scheduler,
     messages: MessageQueues,
-    active_meters: Vec<ActiveMeterType>,
+    active_meters: Loadout,
     passive_meters: Vec<PassiveMeterType>,
     levels: Vec<TerrainInfo>,
     level_index: usize,
     player_turn_events: Vec<PlayerTurnEventEntry>,
     visibility_grid: VisibilityGrid,
+    vision_radius: u32,
+    stats: Stats,
+    combat_log: VecDeque<Hit>,
+    visited: HashSet<Coord>,
 }
 
 fn shuffled_unequipped_meters<R: Rng>(world: &World, id: EntityId, rng: &mut R) -> Vec<MeterType> {
@@ -281,6 +520,39 @@ impl State {
         self.switch_levels(Some(upgrade));
     }
 
+    pub fn switch_levels_swap(&mut self, discard: MeterType, upgrade: MeterType) {
+        self.discard_meter(discard);
+        self.switch_levels(Some(upgrade));
+    }
+
+    pub fn discardable_meters(&self) -> Vec<MeterType> {
+        self.active_meters
+            .meters()
+            .map(|(_, typ)| typ.typ())
+            .chain(self.passive_meters.iter().map(|typ| typ.typ()))
+            .filter(|&typ| typ != MeterType::Health)
+            .collect()
+    }
+
+    fn discard_meter(&mut self, typ: MeterType) {
+        self.world
+            .commit(EntityChange::Remove(self.player_id, typ.into()));
+        if typ == MeterType::Stamina {
+            self.world.commit(remove::stamina_tick(self.player_id));
+        }
+        self.player_turn_events
+            .retain(|entry| entry.event.meter_type() != typ);
+        match typ.active_or_passive() {
+            ActiveOrPassive::Active(typ) => {
+                self.active_meters.remove(typ);
+                if self.selected_meter == Some(typ) {
+                    self.selected_meter = None;
+                }
+            }
+            ActiveOrPassive::Passive(typ) => self.passive_meters.retain(|&t| t != typ),
+        }
+    }
+
     pub fn upgrade_choices(&mut self) -> Vec<MeterType> {
         const NUM_CHOICES: usize = 3;
         let types = shuffled_unequipped_meters(&self.world, self.player_id, &mut self.rng);
@@ -290,6 +562,8 @@ impl State {
 
     fn switch_levels(&mut self, upgrade: Option<MeterType>) {
         self.level_index += 1;
+        self.messages.noises.clear();
+        self.messages.solidity_changed.clear();
         let mut next_world = World::new(
             &self.levels[self.level_index],
             &mut self.messages,
@@ -311,6 +585,10 @@ impl State {
                 // otherwise the player would be moved to their old position in the new level
                 continue;
             }
+            if change.typ() == ComponentType::Shield {
+                // the shield overlay stays behind in the old level
+                continue;
+            }
 
             next_world.commit(change);
         }
@@ -325,7 +603,7 @@ impl State {
                 ));
                 match upgrade.active_or_passive() {
                     ActiveOrPassive::Active(typ) => {
-                        self.active_meters.push(typ);
+                        self.active_meters.insert(typ);
                         let general_typ: MeterType = typ.into();
                         if let Some(change) = general_typ.periodic_change() {
                             let event = PlayerTurnEvent::ChangeActiveMeter(typ, change.change);
@@ -355,13 +633,16 @@ impl State {
 
         self.player_id = next_player_id;
         self.world = next_world;
-        self.turn = TurnState::Player;
+        self.scheduler.clear();
+        self.start_round();
+        self.visited.clear();
 
         self.visibility_grid.clear();
+        self.light.invalidate();
         self.update_visibility();
 
         self.pathfinding
-            .update_player_map(player_coord, &self.world.spatial_hash);
+            .populate_player_map(player_coord, &self.world.spatial_hash);
     }
 
     pub fn new(rng_seed: usize) -> Self {
@@ -431,11 +712,14 @@ impl State {
             random_meter.player_component_value(),
         ));
 
-        let active_meters: Vec<_> = world
+        let mut active_meters = Loadout::new();
+        for typ in world
             .entity_components
             .component_types(player_id)
             .filter_map(|typ| MeterType::from_component_type(typ).and_then(|typ| typ.active()))
-            .collect();
+        {
+            active_meters.insert(typ);
+        }
 
         let passive_meters: Vec<_> = world
             .entity_components
@@ -445,7 +729,7 @@ impl State {
 
         let mut player_turn_events = Vec::new();
 
-        for typ in active_meters.iter().cloned() {
+        for (_, typ) in active_meters.meters() {
             let general_typ: MeterType = typ.into();
             if let Some(change) = general_typ.periodic_change() {
                 let event = PlayerTurnEvent::ChangeActiveMeter(typ, change.change);
@@ -464,17 +748,25 @@ impl State {
         }
 
         let mut pathfinding = PathfindingContext::new(world.size());
+        let noise = NoiseContext::new(world.size());
+        let bfs = BfsContext::new(world.size());
 
-        pathfinding.update_player_map(player_coord, &world.spatial_hash);
+        pathfinding.populate_player_map(player_coord, &world.spatial_hash);
+
+        let mut scheduler = Scheduler::new();
+        scheduler.start_round(&world.entity_store);
 
         Self {
             player_id,
             rng,
             turn: TurnState::Player,
+            scheduler,
             messages,
             swap_messages: MessageQueuesSwap::new(),
             pathfinding,
             visibility_grid: VisibilityGrid::new(world.size()),
+            vision_radius: light::DEFAULT_VISION_RADIUS,
+            light: LightContext::new(world.size()),
             npc_order: Vec::new(),
             seen_animation_channels: HashSet::new(),
             change_context: ChangeContext::new(),
@@ -486,7 +778,12 @@ impl State {
             level_index,
             player_turn_events,
             shadowcast: ShadowcastContext::new(),
+            noise,
+            bfs,
             rng_seed,
+            stats: Stats::default(),
+            combat_log: VecDeque::new(),
+            visited: HashSet::new(),
         }
     }
 
@@ -503,6 +800,7 @@ impl State {
             next_rng_seed,
             size: self.world.size(),
             turn: self.turn,
+            scheduler: self.scheduler.clone(),
             messages: self.messages.clone(),
             active_meters: self.active_meters.clone(),
             passive_meters: self.passive_meters.clone(),
@@ -510,6 +808,10 @@ impl State {
             level_index: self.level_index,
             player_turn_events: self.player_turn_events.clone(),
             visibility_grid: self.visibility_grid.clone(),
+            vision_radius: self.vision_radius,
+            stats: self.stats,
+            combat_log: self.combat_log.clone(),
+            visited: self.visited.clone(),
         }
     }
 
@@ -517,29 +819,143 @@ impl State {
         self.visibility_grid.iter(self.world.count)
     }
 
+    // what the player knows about the contents of a cell, if anything
+    pub fn known_cell(&self, coord: Coord) -> Option<(slice::Iter<'_, TileInfo>, Visibility)> {
+        let time = self.world.count;
+        self.visibility_grid.get(coord).and_then(|cell| {
+            if cell.last_updated == 0 {
+                None
+            } else if cell.last_updated == time {
+                Some((cell.tiles.iter(), Visibility::Visible))
+            } else {
+                Some((cell.tiles.iter(), Visibility::Remembered))
+            }
+        })
+    }
+
+    pub fn player_coord(&self) -> Coord {
+        *self.world.entity_store.coord.get(&self.player_id).unwrap()
+    }
+
+    pub fn size(&self) -> Size {
+        self.world.size()
+    }
+
+    // true when the next call to `tick` will act on an input
+    pub fn is_awaiting_input(&self) -> bool {
+        self.messages.animations.is_empty()
+            && match self.turn {
+                TurnState::Player => self.scheduler.is_ready(self.player_id),
+                _ => false,
+            }
+    }
+
+    pub fn visible_npcs(&self) -> HashSet<EntityId> {
+        let time = self.world.count;
+        let entity_store = &self.world.entity_store;
+        entity_store
+            .npc
+            .keys()
+            .filter(|id| {
+                entity_store
+                    .coord
+                    .get(id)
+                    .and_then(|&coord| self.visibility_grid.get(coord))
+                    .map(|cell| cell.last_updated == time)
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect()
+    }
+
+    // Populates `path` with the shortest route from the player to
+    // `destination` through cells the player knows are passable. Returns
+    // false if there is no such route.
+    pub fn travel_path(&mut self, destination: Coord, path: &mut Vec<Direction>) -> bool {
+        let player_coord = self.player_coord();
+        self.visibility_grid.travel_path(
+            &mut self.bfs,
+            self.world.count,
+            player_coord,
+            destination,
+            path,
+        )
+    }
+
+    // Populates `path` with the shortest route from the player to the nearest
+    // cell which is next to an unknown cell, or which contains a pickup the
+    // player hasn't visited. Returns false if there is nowhere left to explore.
+    pub fn explore_path(&mut self, path: &mut Vec<Direction>) -> bool {
+        let player_coord = self.player_coord();
+        self.visibility_grid.explore_path(
+            &mut self.bfs,
+            self.world.count,
+            player_coord,
+            &self.visited,
+            path,
+        )
+    }
+
+    // npcs in cells which are no longer visible, where they were last seen
+    pub fn remembered_npcs(&self) -> RememberedNpcIter<'_> {
+        self.visibility_grid.remembered_npcs(self.world.count)
+    }
+
+    pub fn vision_radius(&self) -> u32 {
+        self.vision_radius
+    }
+
+    pub fn set_vision_radius(&mut self, vision_radius: u32) {
+        self.vision_radius = vision_radius;
+    }
+
     fn update_visibility(&mut self) {
         let &player_coord = self.world.entity_store.coord.get(&self.player_id).unwrap();
+        self.light.update(
+            self.world.count,
+            &self.world.entity_store,
+            &self.world.spatial_hash,
+        );
         let mut output_grid = VisibilityRefs {
             grid: &mut self.visibility_grid,
             world: &self.world,
+            light: &self.light,
         };
         for_each_visible_cell(
             player_coord,
             self.world.count,
+            self.vision_radius,
             &mut output_grid,
             &mut self.shadowcast,
         );
+        for_each_flare_lit_cell(self.world.count, &mut output_grid);
     }
 
     pub fn player_active_meter_info(&self) -> ActiveMeterInfoIter {
         ActiveMeterInfoIter {
             entity_store: &self.world.entity_store,
-            meter_metadata: self.active_meters.iter().enumerate(),
+            meter_metadata: self.active_meters.meters(),
             entity_id: self.player_id,
             selected_meter: self.selected_meter,
         }
     }
 
+    pub fn loadout(&self) -> &Loadout {
+        &self.active_meters
+    }
+
+    pub fn swap_loadout_slots(&mut self, a: ActiveMeterIdentifier, b: ActiveMeterIdentifier) {
+        self.active_meters.swap(a, b);
+    }
+
+    pub fn toggle_loadout_pin(&mut self, identifier: ActiveMeterIdentifier) {
+        self.active_meters.toggle_pin(identifier);
+    }
+
+    pub fn set_loadout_pins(&mut self, pins: LoadoutPins) {
+        self.active_meters.set_pins(pins);
+    }
+
     fn compass_meter(&self) -> Meter {
         const MAX_DISTANCE: i32 = 40;
         let mut closest = BestSet::new();
@@ -574,6 +990,24 @@ impl State {
         }
     }
 
+    pub fn player_status_effects(&self) -> StatusEffects {
+        self.world
+            .entity_store
+            .status_effects
+            .get(&self.player_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    // most recent hits first
+    pub fn combat_log(&self) -> vec_deque::Iter<'_, Hit> {
+        self.combat_log.iter()
+    }
+
     pub fn entity_store(&self) -> &EntityStore {
         &self.world.entity_store
     }
@@ -641,6 +1075,8 @@ impl State {
                 coord += direction.coord();
             }
 
+            self.messages.noise(entity_coord, RAIL_GUN_NOISE_RADIUS);
+
             ammo.value -= 1;
             self.messages
                 .change(insert::rail_gun_meter(self.player_id, ammo));
@@ -682,6 +1118,9 @@ impl State {
 
                 common_animations::bullet(bullet_id, &mut self.messages);
             }
+            let flash_id = self.world.id_allocator.allocate();
+            prototypes::muzzle_flash(flash_id, entity_coord, &mut self.messages);
+            self.messages.noise(entity_coord, GUN_NOISE_RADIUS);
             ammo.value -= 1;
             self.messages
                 .change(insert::gun_meter(self.player_id, ammo));
@@ -691,6 +1130,142 @@ impl State {
         }
     }
 
+    fn use_grenade(&mut self, direction: CardinalDirection) -> Result<(), Alert> {
+        let mut ammo = self
+            .world
+            .entity_store
+            .grenade_meter
+            .get(&self.player_id)
+            .cloned()
+            .unwrap();
+
+        if ammo.value > 0 {
+            let entity_coord = self
+                .world
+                .entity_store
+                .coord
+                .get(&self.player_id)
+                .cloned()
+                .unwrap();
+
+            let grenade_id = self.world.id_allocator.allocate();
+            prototypes::grenade(
+                grenade_id,
+                entity_coord,
+                direction,
+                weapons::GRENADE_RANGE,
+                &mut self.messages,
+            );
+            common_animations::grenade(grenade_id, &mut self.messages);
+
+            ammo.value -= 1;
+            self.messages
+                .change(insert::grenade_meter(self.player_id, ammo));
+            Ok(())
+        } else {
+            Err(Alert::NoAmmo)
+        }
+    }
+
+    fn use_flare(&mut self) -> Result<(), Alert> {
+        let mut ammo = self
+            .world
+            .entity_store
+            .flare_meter
+            .get(&self.player_id)
+            .cloned()
+            .unwrap();
+
+        if ammo.value > 0 {
+            let entity_coord = self
+                .world
+                .entity_store
+                .coord
+                .get(&self.player_id)
+                .cloned()
+                .unwrap();
+
+            let flare_id = self.world.id_allocator.allocate();
+            prototypes::flare(flare_id, entity_coord, &mut self.messages);
+
+            ammo.value -= 1;
+            self.messages
+                .change(insert::flare_meter(self.player_id, ammo));
+            Ok(())
+        } else {
+            Err(Alert::NoAmmo)
+        }
+    }
+
+    fn use_decoy(&mut self, direction: CardinalDirection) -> Result<(), Alert> {
+        let mut decoy = self
+            .world
+            .entity_store
+            .decoy_meter
+            .get(&self.player_id)
+            .cloned()
+            .unwrap();
+
+        if decoy.value == decoy.max {
+            let current = *self.world.entity_store.coord.get(&self.player_id).unwrap();
+            let next = current + direction.coord();
+
+            if let Some(sh_cell) = self.world.spatial_hash.get(next) {
+                let npc_cell = !sh_cell.npc_set.is_empty() || !sh_cell.decoy_set.is_empty();
+                if sh_cell.solid_count > 0 || npc_cell {
+                    return Err(Alert::BlinkIntoNonEmpty);
+                }
+            }
+
+            decoy.value = 0;
+            self.messages
+                .change(insert::decoy_meter(self.player_id, decoy));
+
+            let decoy_id = self.world.id_allocator.allocate();
+            prototypes::decoy(decoy_id, next, &mut self.messages);
+            Ok(())
+        } else {
+            Err(Alert::NoDecoy)
+        }
+    }
+
+    fn use_shield(&mut self, direction: CardinalDirection) -> Result<(), Alert> {
+        let shield = self
+            .world
+            .entity_store
+            .shield_meter
+            .get(&self.player_id)
+            .cloned()
+            .unwrap();
+
+        if shield.value > 0 {
+            let entity_coord = self
+                .world
+                .entity_store
+                .coord
+                .get(&self.player_id)
+                .cloned()
+                .unwrap();
+
+            for &overlay_id in self.world.entity_store.shield_overlay.iter() {
+                self.messages.remove(overlay_id);
+            }
+
+            self.messages
+                .change(insert::shield(self.player_id, direction));
+            let overlay_id = self.world.id_allocator.allocate();
+            prototypes::shield_overlay(
+                overlay_id,
+                entity_coord + direction.coord(),
+                direction,
+                &mut self.messages,
+            );
+            Ok(())
+        } else {
+            Err(Alert::NoShield)
+        }
+    }
+
     fn use_push(&mut self) -> Result<(), Alert> {
         let mut push = self
             .world
@@ -771,6 +1346,29 @@ impl State {
             Err(Alert::NoAmmo)
         }
     }
+    fn use_haste(&mut self) -> Result<(), Alert> {
+        let mut haste = self
+            .world
+            .entity_store
+            .haste_meter
+            .get(&self.player_id)
+            .cloned()
+            .unwrap();
+        if haste.value == haste.max {
+            haste.value = 0;
+            self.messages
+                .change(insert::haste_meter(self.player_id, haste));
+            status::apply(
+                self.player_id,
+                StatusEffectType::Hasted,
+                status::HASTED_TURNS,
+                &mut self.messages,
+            );
+            Ok(())
+        } else {
+            Err(Alert::NoHaste)
+        }
+    }
     fn use_medkit(&mut self) -> Result<(), Alert> {
         let mut medkit = self
             .world
@@ -808,7 +1406,7 @@ impl State {
         if let Some(sh_cell) = self.world.spatial_hash.get(next) {
             let door_cell = sh_cell.door_count > 0;
             let solid_cell = sh_cell.solid_count > 0 && !door_cell;
-            if solid_cell {
+            if solid_cell || !sh_cell.decoy_set.is_empty() {
                 return Err(Alert::WalkIntoWall);
             }
         }
@@ -839,7 +1437,7 @@ impl State {
 
             if let Some(sh_cell) = self.world.spatial_hash.get(next) {
                 let door_cell = sh_cell.door_count > 0;
-                let npc_cell = !sh_cell.npc_set.is_empty();
+                let npc_cell = !sh_cell.npc_set.is_empty() || !sh_cell.decoy_set.is_empty();
                 let solid_cell = sh_cell.solid_count > 0 && !door_cell;
                 if solid_cell || npc_cell {
                     return Err(Alert::BlinkIntoNonEmpty);
@@ -869,6 +1467,8 @@ impl State {
                     Some(ActiveMeterType::Medkit) => return None,
                     Some(ActiveMeterType::Metabol) => return None,
                     Some(ActiveMeterType::Push) => return None,
+                    Some(ActiveMeterType::Haste) => return None,
+                    Some(ActiveMeterType::Flare) => return None,
                     Some(ActiveMeterType::Blink) => {
                         if let Err(alert) = self.blink(direction) {
                             self.selected_meter = None;
@@ -881,12 +1481,30 @@ impl State {
                             return Some(Event::External(ExternalEvent::Alert(alert)));
                         }
                     }
+                    Some(ActiveMeterType::Shield) => {
+                        if let Err(alert) = self.use_shield(direction) {
+                            self.selected_meter = None;
+                            return Some(Event::External(ExternalEvent::Alert(alert)));
+                        }
+                    }
+                    Some(ActiveMeterType::Decoy) => {
+                        if let Err(alert) = self.use_decoy(direction) {
+                            self.selected_meter = None;
+                            return Some(Event::External(ExternalEvent::Alert(alert)));
+                        }
+                    }
+                    Some(ActiveMeterType::Grenade) => {
+                        if let Err(alert) = self.use_grenade(direction) {
+                            self.selected_meter = None;
+                            return Some(Event::External(ExternalEvent::Alert(alert)));
+                        }
+                    }
                 }
 
                 self.selected_meter = None;
             }
             Input::ActiveMeterSelect(identifier) => {
-                if let Some(meter_type) = self.active_meters.get(identifier.to_index()).cloned() {
+                if let Some(meter_type) = self.active_meters.get(identifier) {
                     match meter_type {
                         ActiveMeterType::Gun => {
                             if let Err(alert) = self.use_gun() {
@@ -908,6 +1526,16 @@ impl State {
                                 return Some(Event::External(ExternalEvent::Alert(alert)));
                             }
                         }
+                        ActiveMeterType::Flare => {
+                            if let Err(alert) = self.use_flare() {
+                                return Some(Event::External(ExternalEvent::Alert(alert)));
+                            }
+                        }
+                        ActiveMeterType::Haste => {
+                            if let Err(alert) = self.use_haste() {
+                                return Some(Event::External(ExternalEvent::Alert(alert)));
+                            }
+                        }
                         ActiveMeterType::Blink => {
                             self.selected_meter = Some(meter_type);
                             return Some(Event::External(ExternalEvent::Alert(
@@ -920,6 +1548,24 @@ impl State {
                                 Alert::RailgunWhichDirection,
                             )));
                         }
+                        ActiveMeterType::Grenade => {
+                            self.selected_meter = Some(meter_type);
+                            return Some(Event::External(ExternalEvent::Alert(
+                                Alert::GrenadeWhichDirection,
+                            )));
+                        }
+                        ActiveMeterType::Shield => {
+                            self.selected_meter = Some(meter_type);
+                            return Some(Event::External(ExternalEvent::Alert(
+                                Alert::ShieldWhichDirection,
+                            )));
+                        }
+                        ActiveMeterType::Decoy => {
+                            self.selected_meter = Some(meter_type);
+                            return Some(Event::External(ExternalEvent::Alert(
+                                Alert::DecoyWhichDirection,
+                            )));
+                        }
                     }
                 } else {
                     return Some(Event::External(ExternalEvent::Alert(Alert::NoSuchMeter)));
@@ -945,7 +1591,10 @@ impl State {
             }
         }
 
-        self.turn = TurnState::Npcs;
+        self.scheduler.spend(self.player_id);
+        if !self.scheduler.is_ready(self.player_id) {
+            self.turn = TurnState::Npcs;
+        }
 
         let ret = self.change_context.process(
             &mut self.world,
@@ -954,17 +1603,162 @@ impl State {
             &mut self.rng,
         );
 
+        if let Some(player_coord) = self.messages.player_moved_to {
+            self.visited.insert(player_coord);
+        }
+
         self.process_turn_events();
 
         ret
     }
 
-    fn fast_npc_turns(&mut self) -> Option<Event> {
+    fn npc_sees_player(&self, id: EntityId, time: u64) -> bool {
+        let coord = self.world.entity_store.coord.get(&id).unwrap();
+        self.visibility_grid
+            .get(*coord)
+            .map(|visibility| visibility.last_in_sight == time)
+            .unwrap_or(false)
+    }
+
+    fn remember_player(&self, id: EntityId) -> Option<EntityChange> {
+        let entity_store = &self.world.entity_store;
+        if self.npc_sees_player(id, self.world.count) {
+            let player_coord = *entity_store.coord.get(&self.player_id).unwrap();
+            Some(insert::remembered_target(id, player_coord))
+        } else if entity_store.remembered_target.get(&id) == entity_store.coord.get(&id) {
+            // reached the last known position without finding the player
+            Some(remove::remembered_target(id))
+        } else {
+            None
+        }
+    }
+
+    fn npc_target(&self, id: EntityId, time: u64) -> NpcTarget {
+        if let Some(nest) = self.retreat_target(id) {
+            return NpcTarget::LastKnown(nest);
+        }
+        if self.npc_sees_player(id, time) {
+            return NpcTarget::Player;
+        }
+        let entity_store = &self.world.entity_store;
+        match entity_store.remembered_target.get(&id) {
+            Some(&target) if Some(&target) != entity_store.coord.get(&id) => {
+                NpcTarget::LastKnown(target)
+            }
+            _ => NpcTarget::Wander,
+        }
+    }
+
+    // Direction from a spitter to the player if the player is within range along
+    // an unobstructed cardinal line
+    fn spit_direction(&self, id: EntityId) -> Option<CardinalDirection> {
+        let coord = *self.world.entity_store.coord.get(&id).unwrap();
+        for direction in CardinalDirections {
+            let mut current = coord;
+            for _ in 0..weapons::SPIT_RANGE {
+                current += direction.coord();
+                if let Some(sh_cell) = self.world.spatial_hash.get(current) {
+                    if sh_cell.player_count > 0 {
+                        return Some(direction);
+                    }
+                    if sh_cell.solid_count > 0 || !sh_cell.npc_set.is_empty() {
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+        }
+        None
+    }
+
+    // Where a retreating npc is heading, if it hasn't arrived yet
+    fn retreat_target(&self, id: EntityId) -> Option<Coord> {
+        let entity_store = &self.world.entity_store;
+        if !entity_store.retreat.contains(&id) {
+            return None;
+        }
+        entity_store
+            .nest
+            .get(&id)
+            .cloned()
+            .filter(|nest| Some(nest) != entity_store.coord.get(&id))
+    }
+
+    fn npc_act(&mut self, id: EntityId, time: u64) {
+        if let Some(status_effects) = self.world.entity_store.status_effects.get(&id) {
+            if status_effects.contains(StatusEffectType::Stunned) {
+                return;
+            }
+        }
+        if self.world.entity_store.retreat.contains(&id) && self.retreat_target(id).is_none() {
+            self.messages.change(remove::retreat(id));
+        }
+        let target = self.npc_target(id, time);
+        if let NpcTarget::Player = target {
+            if self.world.entity_store.spitter.contains(&id) {
+                let coord = *self.world.entity_store.coord.get(&id).unwrap();
+                if let Some(direction) = self.spit_direction(id) {
+                    let spit_id = self.world.id_allocator.allocate();
+                    prototypes::spit(
+                        spit_id,
+                        coord + direction.coord(),
+                        direction,
+                        weapons::SPIT_RANGE,
+                        &mut self.messages,
+                    );
+                    common_animations::spit(spit_id, &mut self.messages);
+                    return;
+                }
+                let too_close = self
+                    .pathfinding
+                    .distance_to_player(coord)
+                    .map(|distance| distance < weapons::SPITTER_MIN_DISTANCE)
+                    .unwrap_or(false);
+                if too_close {
+                    self.pathfinding.flee(
+                        id,
+                        &self.world.entity_store,
+                        &self.world.spatial_hash,
+                        PathfindingConfig::for_entity(id, &self.world.entity_store),
+                        &mut self.messages,
+                    );
+                    return;
+                }
+            }
+        }
+        self.pathfinding.act(
+            id,
+            &self.world.entity_store,
+            &self.world.spatial_hash,
+            target,
+            &mut self.rng,
+            &mut self.messages,
+        );
+    }
+
+    fn start_round(&mut self) {
+        self.scheduler.start_round(&self.world.entity_store);
         self.turn = TurnState::Player;
+    }
 
+    fn end_npc_turns(&mut self, more_npc_turns: bool) {
+        if more_npc_turns {
+            self.turn = TurnState::ReadyNpcs;
+        } else {
+            self.start_round();
+        }
+    }
+
+    // turns taken by npcs with energy left over after every npc has acted
+    fn ready_npc_turns(&mut self) -> Option<Event> {
+        let time = self.world.count;
+
+        let mut stealth_rolls = StealthRolls::new(self.player_id, &self.world.entity_store);
+        let mut more_npc_turns = false;
         self.npc_order.clear();
         for (&id, info) in self.world.entity_store.npc.iter() {
-            if !info.fast {
+            if !info.mobile || !self.scheduler.is_ready(id) {
                 continue;
             }
             let active = if info.active {
@@ -972,7 +1766,8 @@ impl State {
             } else {
                 let coord = self.world.entity_store.coord.get(&id).unwrap();
                 let visibility = self.visibility_grid.get(*coord).unwrap();
-                if visibility.last_updated == self.world.count {
+                let in_sight = visibility.last_in_sight == self.world.count;
+                if stealth_rolls.noticed(in_sight, &mut self.rng) {
                     self.messages.change(insert::npc(
                         id,
                         NpcInfo {
@@ -986,21 +1781,29 @@ impl State {
                 }
             };
             if active {
+                if let Some(change) = self.remember_player(id) {
+                    self.messages.change(change);
+                }
+                self.scheduler.spend(id);
+                more_npc_turns = more_npc_turns || self.scheduler.is_ready(id);
                 self.npc_order.push(id);
             }
         }
 
+        stealth_rolls.spend(self.player_id, &mut self.messages);
+        self.end_npc_turns(more_npc_turns);
+
         self.pathfinding
             .sort_entities_by_distance_to_player(&self.world.entity_store, &mut self.npc_order);
+        self.pathfinding.clear_reservations();
 
-        for &id in self.npc_order.iter() {
-            self.pathfinding.act(
-                id,
-                &self.world.entity_store,
-                &self.world.spatial_hash,
-                PathfindingConfig { open_doors: true },
-                &mut self.messages,
-            );
+        for i in 0..self.npc_order.len() {
+            let id = self.npc_order[i];
+            if !self.world.entity_store.coord.contains_key(&id) {
+                // killed earlier in the turn, e.g. by friendly fire
+                continue;
+            }
+            self.npc_act(id, time);
             if let Some(meta) = self.change_context.process(
                 &mut self.world,
                 &mut self.messages,
@@ -1014,22 +1817,152 @@ impl State {
         None
     }
 
+    fn grenade_turns(&mut self) {
+        for &id in self.world.entity_store.grenade.keys() {
+            let countdown = if let Some(&countdown) = self.world.entity_store.countdown.get(&id) {
+                countdown
+            } else {
+                // still in flight
+                continue;
+            };
+            if countdown > 0 {
+                self.messages.change(insert::countdown(id, countdown - 1));
+                continue;
+            }
+            if let Some(&coord) = self.world.entity_store.coord.get(&id) {
+                common_animations::area_damage(
+                    coord,
+                    weapons::GRENADE_EXPLOSION,
+                    DamageSource::Player,
+                    &self.world.spatial_hash,
+                    &mut self.world.id_allocator,
+                    &mut self.messages,
+                );
+            }
+            self.messages.remove(id);
+        }
+    }
+
+    fn temporary_entity_turns(&mut self) {
+        let entity_store = &self.world.entity_store;
+        for &id in entity_store
+            .flare
+            .iter()
+            .chain(entity_store.decoy.iter())
+            .chain(entity_store.muzzle_flash.iter())
+        {
+            if let Some(&countdown) = entity_store.countdown.get(&id) {
+                if countdown > 0 {
+                    self.messages.change(insert::countdown(id, countdown - 1));
+                } else {
+                    self.messages.remove(id);
+                }
+            }
+        }
+    }
+
+    fn status_effect_turns(&mut self) {
+        for (&id, status_effects) in self.world.entity_store.status_effects.iter() {
+            let poisoned = status_effects
+                .get(StatusEffectType::Poisoned)
+                .map(|meter| meter.value % 2 == 0)
+                .unwrap_or(false);
+            // whoever caused the effects is responsible for their damage
+            let source = self
+                .world
+                .entity_store
+                .damaged_by
+                .get(&id)
+                .cloned()
+                .unwrap_or(DamageSource::Npc);
+            let mut damages = Vec::new();
+            if status_effects.contains(StatusEffectType::Burning) {
+                damages.push(Damage::new(1, DamageType::Blast, source));
+            }
+            if poisoned {
+                damages.push(Damage::new(1, DamageType::Chemical, source));
+            }
+            if damage::apply_all(id, &damages, &self.world.entity_store, &mut self.messages) > 0 {
+                common_animations::damage_flash(id, &mut self.messages);
+            }
+            let mut status_effects = *status_effects;
+            status_effects.tick();
+            status::commit(
+                id,
+                status_effects,
+                &self.world.entity_store,
+                &mut self.messages,
+            );
+        }
+    }
+
+    fn process_noises(&mut self) {
+        for noise in self.messages.noises.drain(..) {
+            let entity_store = &self.world.entity_store;
+            let messages = &mut self.messages.changes;
+            self.noise.for_each_woken_npc(
+                noise,
+                entity_store,
+                &self.world.spatial_hash,
+                &mut self.rng,
+                |id| {
+                    // go and investigate the source of the noise
+                    messages.push(insert::remembered_target(id, noise.coord));
+                    if let Some(&info) = entity_store.npc.get(&id) {
+                        messages.push(insert::npc(
+                            id,
+                            NpcInfo {
+                                active: true,
+                                ..info
+                            },
+                        ));
+                    }
+                },
+            );
+        }
+    }
+
     fn all_npc_turns(&mut self) -> Option<Event> {
+        for coord in self.messages.solidity_changed.drain(..) {
+            self.pathfinding
+                .update_cell(coord, &self.world.spatial_hash);
+        }
         if let Some(player_coord) = self.messages.player_moved_to.take() {
             self.pathfinding
                 .update_player_map(player_coord, &self.world.spatial_hash);
         }
 
-        let mut at_least_one_fast = false;
+        let time = self.world.count;
+
+        self.grenade_turns();
+        self.temporary_entity_turns();
+        self.status_effect_turns();
+        self.process_noises();
+
+        let entity_store = &self.world.entity_store;
+        self.pathfinding.update_attractors(
+            entity_store.decoy.iter().filter_map(|id| {
+                entity_store.coord.get(id).map(|&coord| Attractor {
+                    coord,
+                    priority: 1,
+                    range: weapons::DECOY_RANGE,
+                })
+            }),
+            &self.world.spatial_hash,
+        );
+
+        let mut stealth_rolls = StealthRolls::new(self.player_id, &self.world.entity_store);
+
+        let mut more_npc_turns = false;
         self.npc_order.clear();
         for (&id, info) in self.world.entity_store.npc.iter() {
-            at_least_one_fast = at_least_one_fast || info.fast;
             let active = if info.active {
                 true
             } else {
                 let coord = self.world.entity_store.coord.get(&id).unwrap();
                 let visibility = self.visibility_grid.get(*coord).unwrap();
-                if visibility.last_updated == self.world.count {
+                let in_sight = visibility.last_in_sight == self.world.count;
+                if stealth_rolls.noticed(in_sight, &mut self.rng) {
                     self.messages.change(insert::npc(
                         id,
                         NpcInfo {
@@ -1042,7 +1975,14 @@ impl State {
                     false
                 }
             };
-            if active && info.mobile {
+            if active {
+                if let Some(change) = self.remember_player(id) {
+                    self.messages.change(change);
+                }
+            }
+            if active && info.mobile && self.scheduler.is_ready(id) {
+                self.scheduler.spend(id);
+                more_npc_turns = more_npc_turns || self.scheduler.is_ready(id);
                 self.npc_order.push(id);
             }
 
@@ -1054,7 +1994,7 @@ impl State {
                 if countdown == 0 {
                     if let Some(&transform) = self.world.entity_store.transform.get(&id) {
                         if let Some(&coord) = self.world.entity_store.coord.get(&id) {
-                            self.messages.change(remove::delayed_transform(id));
+                            self.messages.change(remove::status_effects(id));
                             match transform {
                                 Transform::Chrysalis => {
                                     prototypes::chrysalis(
@@ -1095,25 +2035,23 @@ impl State {
             }
         }
 
-        if at_least_one_fast {
-            self.turn = TurnState::FastNpcs;
-        } else {
-            self.turn = TurnState::Player;
-        }
+        stealth_rolls.spend(self.player_id, &mut self.messages);
+
+        self.end_npc_turns(more_npc_turns);
 
         self.pathfinding
             .sort_entities_by_distance_to_player(&self.world.entity_store, &mut self.npc_order);
+        self.pathfinding.clear_reservations();
 
         let mut event = None;
 
-        for &id in self.npc_order.iter() {
-            self.pathfinding.act(
-                id,
-                &self.world.entity_store,
-                &self.world.spatial_hash,
-                PathfindingConfig { open_doors: true },
-                &mut self.messages,
-            );
+        for i in 0..self.npc_order.len() {
+            let id = self.npc_order[i];
+            if !self.world.entity_store.coord.contains_key(&id) {
+                // killed earlier in the turn, e.g. by friendly fire
+                continue;
+            }
+            self.npc_act(id, time);
             if let Some(Event::External(meta)) = self.change_context.process(
                 &mut self.world,
                 &mut self.messages,
@@ -1158,11 +2096,46 @@ impl State {
         )
     }
 
+    fn active_npc_adjacent_to_player(&self) -> bool {
+        let player_coord = *self.world.entity_store.coord.get(&self.player_id).unwrap();
+        CardinalDirections.into_iter().any(|direction| {
+            self.world
+                .spatial_hash
+                .get(player_coord + direction.coord())
+                .map(|sh_cell| {
+                    sh_cell.npc_set.iter().any(|id| {
+                        self.world
+                            .entity_store
+                            .npc
+                            .get(id)
+                            .map(|info| info.active)
+                            .unwrap_or(false)
+                    })
+                })
+                .unwrap_or(false)
+        })
+    }
+
     fn process_turn_events(&mut self) -> Option<Event> {
+        let active_npc_adjacent = self.active_npc_adjacent_to_player();
         for entry in self.player_turn_events.iter_mut() {
             if entry.remaining == 0 {
                 let change = match entry.event {
+                    PlayerTurnEvent::ChangePassiveMeter(PassiveMeterType::Regeneration, _)
+                        if active_npc_adjacent =>
+                    {
+                        // regeneration is paused while in melee
+                        continue;
+                    }
                     PlayerTurnEvent::ChangeActiveMeter(typ, change) => {
+                        let change = if typ == ActiveMeterType::Shield
+                            && self.world.entity_store.shield.contains_key(&self.player_id)
+                        {
+                            // a raised shield drains rather than recharges
+                            -1
+                        } else {
+                            change
+                        };
                         let general_typ: MeterType = typ.into();
                         let mut meter = Meter::from_entity_store(
                             self.player_id,
@@ -1226,7 +2199,11 @@ impl State {
         let event = if self.messages.animations.is_empty() {
             match self.turn {
                 TurnState::Player => {
-                    if let Some(input) = inputs.into_iter().next() {
+                    if !self.scheduler.is_ready(self.player_id) {
+                        // the player is too slow to act this round
+                        self.turn = TurnState::Npcs;
+                        None
+                    } else if let Some(input) = inputs.into_iter().next() {
                         self.player_turn(input)
                     } else {
                         None
@@ -1239,7 +2216,7 @@ impl State {
                         None
                     }
                 }
-                TurnState::FastNpcs => self.fast_npc_turns(),
+                TurnState::ReadyNpcs => self.ready_npc_turns(),
             }
         } else {
             self.animation_tick(period)
@@ -1247,9 +2224,35 @@ impl State {
 
         self.update_visibility();
 
+        let time = self.world.count;
+        let mut alert = None;
+        for event in self.messages.stat_events.drain(..) {
+            self.stats.record(event);
+            if let StatEvent::Hit(hit) = event {
+                // hits on targets the player can't see would give away their position
+                let visible = self
+                    .visibility_grid
+                    .get(hit.coord)
+                    .map(|cell| cell.last_updated == time)
+                    .unwrap_or(false);
+                if !visible {
+                    continue;
+                }
+                if hit.damage.source == DamageSource::Player {
+                    if hit.modifier < 0 {
+                        alert = Some(Alert::DamageResisted);
+                    } else if hit.modifier > 0 {
+                        alert = Some(Alert::DamageWeakness);
+                    }
+                }
+                self.combat_log.push_front(hit);
+                self.combat_log.truncate(COMBAT_LOG_LENGTH);
+            }
+        }
+
         match event {
             Some(Event::External(external_event)) => Some(external_event),
-            None => None,
+            None => alert.map(ExternalEvent::Alert),
         }
     }
 }
@@ -1262,6 +2265,7 @@ impl From<SaveState> for State {
             next_rng_seed,
             size,
             turn,
+            scheduler,
             messages,
             active_meters,
             passive_meters,
@@ -1269,6 +2273,10 @@ impl From<SaveState> for State {
             level_index,
             player_turn_events,
             visibility_grid,
+            vision_radius,
+            stats,
+            combat_log,
+            visited,
         }: SaveState,
     ) -> Self {
         Self {
@@ -1276,6 +2284,7 @@ impl From<SaveState> for State {
             player_id,
             rng: StdRng::seed_from_u64(next_rng_seed as u64),
             turn,
+            scheduler,
             messages,
             swap_messages: MessageQueuesSwap::new(),
             pathfinding: PathfindingContext::new(size),
@@ -1289,8 +2298,113 @@ impl From<SaveState> for State {
             level_index,
             player_turn_events,
             shadowcast: ShadowcastContext::new(),
+            noise: NoiseContext::new(size),
+            bfs: BfsContext::new(size),
             visibility_grid,
+            vision_radius,
+            light: LightContext::new(size),
             rng_seed: next_rng_seed,
+            stats,
+            combat_log,
+            visited,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TIME: u64 = 2;
+
+    // '#' wall, '.' floor, '>' stairs, '$' pickup, 'N' npc in view, 'n' npc
+    // remembered from an earlier turn, ' ' never seen
+    fn visibility_grid(rows: &[&str]) -> VisibilityGrid {
+        let size = Size::new(rows[0].len() as u32, rows.len() as u32);
+        VisibilityGrid(Grid::new_fn(size, |coord| {
+            let ch = rows[coord.y as usize].as_bytes()[coord.x as usize];
+            let mut cell = VisibilityCell::default();
+            let tiles: &[Tile] = match ch {
+                b' ' => return cell,
+                b'#' => &[Tile::Wall],
+                b'.' => &[Tile::Floor],
+                b'>' => &[Tile::Floor, Tile::Stairs],
+                b'$' => &[Tile::Floor, Tile::HealthPickup],
+                b'N' | b'n' => &[Tile::Floor, Tile::Larvae],
+                _ => panic!("unexpected cell {}", ch as char),
+            };
+            cell.tiles = tiles.iter().map(|&tile| TileInfo::new(tile, 0)).collect();
+            if ch == b'N' || ch == b'n' {
+                cell.npc = Some(TileInfo::new(Tile::Larvae, 0));
+            }
+            cell.last_updated = if ch == b'N' { TIME } else { TIME - 1 };
+            cell
+        }))
+    }
+
+    fn travel(grid: &VisibilityGrid, start: Coord, destination: Coord) -> Option<usize> {
+        let mut bfs = BfsContext::new(grid.0.size());
+        let mut path = Vec::new();
+        if grid.travel_path(&mut bfs, TIME, start, destination, &mut path) {
+            Some(path.len())
+        } else {
+            None
         }
     }
+
+    fn explore(grid: &VisibilityGrid, start: Coord, visited: &HashSet<Coord>) -> Option<usize> {
+        let mut bfs = BfsContext::new(grid.0.size());
+        let mut path = Vec::new();
+        if grid.explore_path(&mut bfs, TIME, start, visited, &mut path) {
+            Some(path.len())
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn travel_goes_around_visible_npcs() {
+        let grid = visibility_grid(&["#######", "#.....#", "#N###.#", "#.....#", "#######"]);
+        assert_eq!(travel(&grid, Coord::new(1, 1), Coord::new(1, 3)), Some(10));
+        let grid = visibility_grid(&["#######", "#.....#", "#n###.#", "#.....#", "#######"]);
+        assert_eq!(travel(&grid, Coord::new(1, 1), Coord::new(1, 3)), Some(2));
+    }
+
+    #[test]
+    fn travel_avoids_stairs_unless_they_are_the_destination() {
+        let grid = visibility_grid(&["#######", "#.....#", "#>###.#", "#.....#", "#######"]);
+        assert_eq!(travel(&grid, Coord::new(1, 1), Coord::new(1, 3)), Some(10));
+        assert_eq!(travel(&grid, Coord::new(1, 1), Coord::new(1, 2)), Some(1));
+    }
+
+    #[test]
+    fn travel_only_uses_known_cells() {
+        let grid = visibility_grid(&["#######", "#.....#", "# ###.#", "#.....#", "#######"]);
+        assert_eq!(travel(&grid, Coord::new(1, 1), Coord::new(1, 3)), Some(10));
+        let grid = visibility_grid(&["#####", "#...#", "## ##", "#...#", "#####"]);
+        assert_eq!(travel(&grid, Coord::new(1, 1), Coord::new(1, 3)), None);
+    }
+
+    #[test]
+    fn explore_walks_to_the_nearest_unknown_cell() {
+        let grid = visibility_grid(&["######", "#...  ", "######"]);
+        assert_eq!(explore(&grid, Coord::new(1, 1), &HashSet::new()), Some(2));
+    }
+
+    #[test]
+    fn explore_walks_to_unvisited_pickups() {
+        let grid = visibility_grid(&["#####", "#..$#", "#####"]);
+        let mut visited = HashSet::new();
+        assert_eq!(explore(&grid, Coord::new(1, 1), &visited), Some(2));
+        visited.insert(Coord::new(3, 1));
+        assert_eq!(explore(&grid, Coord::new(1, 1), &visited), None);
+    }
+
+    #[test]
+    fn explore_stops_when_the_level_is_explored() {
+        let grid = visibility_grid(&["#####", "#...#", "#####"]);
+        assert_eq!(explore(&grid, Coord::new(1, 1), &HashSet::new()), None);
+        let grid = visibility_grid(&["#####", "#.N. ", "#####"]);
+        assert_eq!(explore(&grid, Coord::new(1, 1), &HashSet::new()), None);
+    }
 }
@@ -0,0 +1,41 @@
+use damage::Hit;
+use tile::Tile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageSource {
+    Player,
+    Npc,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum StatEvent {
+    Kill(DamageSource),
+    RailGunHit,
+    Hit(Hit),
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub kills_by_player: u32,
+    pub kills_by_npcs: u32,
+    pub rail_gun_hits: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+}
+
+impl Stats {
+    pub fn record(&mut self, event: StatEvent) {
+        match event {
+            StatEvent::Kill(DamageSource::Player) => self.kills_by_player += 1,
+            StatEvent::Kill(DamageSource::Npc) => self.kills_by_npcs += 1,
+            StatEvent::RailGunHit => self.rail_gun_hits += 1,
+            StatEvent::Hit(hit) => {
+                if hit.tile == Tile::Player {
+                    self.damage_taken += hit.dealt as u32;
+                } else if hit.damage.source == DamageSource::Player {
+                    self.damage_dealt += hit.dealt as u32;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use entity_store::*;
+use message_queues::PushMessages;
+use meter::Meter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StatusEffectType {
+    TransformDelayed,
+    Pushed,
+    Poisoned,
+    Slowed,
+    Stunned,
+    Burning,
+    Hasted,
+}
+
+const NUM_STATUS_EFFECT_TYPES: usize = 7;
+
+pub const ALL_STATUS_EFFECT_TYPES: &[StatusEffectType] = &[
+    StatusEffectType::TransformDelayed,
+    StatusEffectType::Pushed,
+    StatusEffectType::Poisoned,
+    StatusEffectType::Slowed,
+    StatusEffectType::Stunned,
+    StatusEffectType::Burning,
+    StatusEffectType::Hasted,
+];
+
+pub const TRANSFORM_DELAYED_TURNS: i32 = 20;
+pub const PUSHED_TURNS: i32 = 1;
+pub const BURNING_TURNS: i32 = 3;
+pub const POISONED_TURNS: i32 = 3;
+pub const STUNNED_TURNS: i32 = 2;
+pub const SLOWED_TURNS: i32 = 6;
+pub const HASTED_TURNS: i32 = 6;
+
+// The set of effects an entity has, without durations. This is filled in on
+// the copy of an entity's `TileInfo` that the visibility grid keeps.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct StatusEffectSet(u8);
+
+impl StatusEffectSet {
+    pub fn contains(self, typ: StatusEffectType) -> bool {
+        self.0 & (1 << typ as u8) != 0
+    }
+    pub fn insert(&mut self, typ: StatusEffectType) {
+        self.0 |= 1 << typ as u8;
+    }
+    pub fn remove(&mut self, typ: StatusEffectType) {
+        self.0 &= !(1 << typ as u8);
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    pub fn iter(self) -> impl Iterator<Item = StatusEffectType> {
+        ALL_STATUS_EFFECT_TYPES
+            .iter()
+            .cloned()
+            .filter(move |&typ| self.contains(typ))
+    }
+}
+
+// Remaining turns of each effect an entity has
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffects {
+    meters: [Option<Meter>; NUM_STATUS_EFFECT_TYPES],
+}
+
+impl StatusEffects {
+    pub fn get(&self, typ: StatusEffectType) -> Option<Meter> {
+        self.meters[typ as usize]
+    }
+    pub fn contains(&self, typ: StatusEffectType) -> bool {
+        self.meters[typ as usize].is_some()
+    }
+    // Adding an effect that is already present refreshes its duration
+    pub fn insert(&mut self, typ: StatusEffectType, turns: i32) {
+        let meter = match self.meters[typ as usize] {
+            Some(meter) if meter.value >= turns => meter,
+            _ => Meter::full(turns),
+        };
+        self.meters[typ as usize] = Some(meter);
+    }
+    pub fn remove(&mut self, typ: StatusEffectType) {
+        self.meters[typ as usize] = None;
+    }
+    // Counts down every effect by a turn, removing those that have expired
+    pub fn tick(&mut self) {
+        for meter in self.meters.iter_mut() {
+            let expired = if let Some(meter) = meter.as_mut() {
+                meter.value -= 1;
+                meter.value <= 0
+            } else {
+                false
+            };
+            if expired {
+                *meter = None;
+            }
+        }
+    }
+    pub fn set(&self) -> StatusEffectSet {
+        let mut set = StatusEffectSet::default();
+        for typ in self.iter().map(|(typ, _)| typ) {
+            set.insert(typ);
+        }
+        set
+    }
+    pub fn is_empty(&self) -> bool {
+        self.meters.iter().all(Option::is_none)
+    }
+    pub fn iter<'a>(&'a self) -> impl 'a + Iterator<Item = (StatusEffectType, Meter)> {
+        ALL_STATUS_EFFECT_TYPES
+            .iter()
+            .filter_map(move |&typ| self.get(typ).map(|meter| (typ, meter)))
+    }
+}
+
+pub fn commit<M: PushMessages>(
+    id: EntityId,
+    status_effects: StatusEffects,
+    entity_store: &EntityStore,
+    messages: &mut M,
+) {
+    if status_effects.is_empty() {
+        if entity_store.status_effects.contains_key(&id) {
+            messages.change(remove::status_effects(id));
+        }
+    } else {
+        messages.change(insert::status_effects(id, status_effects));
+    }
+}
+
+// Status changes are queued rather than committed straight away, so that
+// several changes to one entity's effects in a single batch of changes are
+// combined instead of overwriting one another
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum StatusChange {
+    Apply(EntityId, StatusEffectType, i32),
+    Clear(EntityId, StatusEffectType),
+}
+
+impl StatusChange {
+    fn id(self) -> EntityId {
+        match self {
+            StatusChange::Apply(id, ..) | StatusChange::Clear(id, ..) => id,
+        }
+    }
+}
+
+pub fn apply<M: PushMessages>(id: EntityId, typ: StatusEffectType, turns: i32, messages: &mut M) {
+    messages.status(StatusChange::Apply(id, typ, turns));
+}
+
+pub fn clear<M: PushMessages>(id: EntityId, typ: StatusEffectType, messages: &mut M) {
+    messages.status(StatusChange::Clear(id, typ));
+}
+
+// Combines the queued changes to each entity's effects, and commits each
+// entity's effects once
+pub fn commit_changes<M: PushMessages>(
+    changes: &mut Vec<StatusChange>,
+    entity_store: &EntityStore,
+    messages: &mut M,
+) {
+    // a stable sort keeps the order of changes to each entity
+    changes.sort_by_key(|change| change.id());
+    let mut changes = changes.drain(..).peekable();
+    while let Some(first) = changes.peek().cloned() {
+        let id = first.id();
+        let current = entity_store
+            .status_effects
+            .get(&id)
+            .cloned()
+            .unwrap_or_default();
+        let mut status_effects = current;
+        while let Some(change) = changes.peek().cloned() {
+            if change.id() != id {
+                break;
+            }
+            match change {
+                StatusChange::Apply(_, typ, turns) => status_effects.insert(typ, turns),
+                StatusChange::Clear(_, typ) => status_effects.remove(typ),
+            }
+            changes.next();
+        }
+        if status_effects != current {
+            commit(id, status_effects, entity_store, messages);
+        }
+    }
+}
@@ -16,6 +16,9 @@ const ROOM_PLACEMENT_ATTEMPTS: usize = 100;
 const PRELIM_ROOM_MIN_SIZE: u32 = 3;
 const PRELIM_ROOM_MAX_SIZE: u32 = 4;
 
+// one in this many rooms is lit
+const ROOM_LIGHT_CHANCE_DENOM: u32 = 2;
+
 fn random_between_inclusive<R: Rng>(min: u32, max: u32, rng: &mut R) -> u32 {
     let delta = max - min;
     let random_delta = rng.gen::<u32>() % (delta + 1);
@@ -478,6 +481,16 @@ pub fn populate<R: Rng>(
         }
     }
 
+    for _ in 0..2 {
+        if let Some(coord) = floor_coords.pop() {
+            prototypes::grenade_ammo_pickup(id_allocator.allocate(), coord, messages);
+        }
+    }
+
+    if let Some(coord) = floor_coords.pop() {
+        prototypes::flare_ammo_pickup(id_allocator.allocate(), coord, messages);
+    }
+
     for _ in 0..3 {
         if let Some(coord) = floor_coords.pop() {
             prototypes::kevlar_pickup(id_allocator.allocate(), coord, messages);
@@ -508,6 +521,12 @@ pub fn populate<R: Rng>(
         }
     }
 
+    for _ in 0..(2 + config.level) {
+        if let Some(coord) = floor_coords.pop() {
+            prototypes::volatile_egg(id_allocator.allocate(), coord, messages, rng);
+        }
+    }
+
     for _ in 0..(6 + config.level) {
         if let Some(coord) = floor_coords.pop() {
             prototypes::larvae(id_allocator.allocate(), coord, messages, rng);
@@ -524,6 +543,19 @@ pub fn populate<R: Rng>(
         }
     }
 
+    for _ in 0..::std::cmp::max(config.level - 2, 0) {
+        if let Some(coord) = floor_coords.pop() {
+            prototypes::spitter(id_allocator.allocate(), coord, messages);
+        }
+    }
+
+    for room in rooms.iter() {
+        if rng.gen_range(0, ROOM_LIGHT_CHANCE_DENOM) == 0 {
+            let radius = ::std::cmp::max(room.size.x(), room.size.y()) / 2 + 2;
+            prototypes::room_light(id_allocator.allocate(), room.centre(), radius, messages);
+        }
+    }
+
     for (coord, &cell) in grid.enumerate() {
         match cell {
             Cell::RoomWall => {
@@ -16,6 +16,7 @@ pub enum Tile {
     Larvae,
     Queen,
     Egg,
+    VolatileEgg,
     Aracnoid,
     Beetoid,
     Chrysalis,
@@ -30,4 +31,29 @@ pub enum Tile {
     MetabolAmmoPickup,
     PushWave,
     PushAmmoPickup,
+    Grenade,
+    GrenadeAmmoPickup,
+    Explosion,
+    Flare,
+    FlareAmmoPickup,
+    Decoy,
+    Shield(CardinalDirection),
+    Spitter,
+    Spit,
+}
+
+impl Tile {
+    pub fn is_pickup(self) -> bool {
+        matches!(
+            self,
+            Tile::HealthPickup
+                | Tile::AmmoPickup
+                | Tile::RailGunAmmoPickup
+                | Tile::KevlarPickup
+                | Tile::MetabolAmmoPickup
+                | Tile::PushAmmoPickup
+                | Tile::GrenadeAmmoPickup
+                | Tile::FlareAmmoPickup
+        )
+    }
 }
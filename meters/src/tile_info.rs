@@ -1,5 +1,7 @@
 use tile::Tile;
 use meter::Meter;
+use status::StatusEffectSet;
+use transform::Transform;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TileInfo {
@@ -9,8 +11,8 @@ pub struct TileInfo {
     pub boss: bool,
     pub health_meter: Option<Meter>,
     pub countdown: Option<i32>,
-    pub delayed_transform: bool,
-    pub pushed: bool,
+    pub status_effects: StatusEffectSet,
+    pub transform: Option<Transform>,
 }
 
 impl TileInfo {
@@ -22,8 +24,8 @@ impl TileInfo {
             boss: false,
             health_meter: None,
             countdown: None,
-            delayed_transform: false,
-            pushed: false,
+            status_effects: StatusEffectSet::default(),
+            transform: None,
         }
     }
     pub fn with_health(tile: Tile, depth: i32, health_meter: Meter) -> Self {
@@ -34,8 +36,8 @@ impl TileInfo {
             boss: false,
             health_meter: Some(health_meter),
             countdown: None,
-            delayed_transform: false,
-            pushed: false,
+            status_effects: StatusEffectSet::default(),
+            transform: None,
         }
     }
 }
@@ -1,5 +1,8 @@
 pub const PUNCH_MILLIS: u64 = 100;
 pub const BULLET_SLIDE_MILLIS: u64 = 32;
+pub const SPIT_SLIDE_MILLIS: u64 = 96;
+pub const GRENADE_SLIDE_MILLIS: u64 = 64;
 pub const DAMAGE_FLASH_MILLIS: u64 = 50;
 pub const RAIL_GUN_SHOT_MILLIS: u64 = 200;
 pub const WAVE_MILLIS: u64 = 80;
+pub const EXPLOSION_MILLIS: u64 = 150;
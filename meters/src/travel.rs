@@ -0,0 +1,144 @@
+use direction::*;
+use entity_store::EntityId;
+use grid_2d::Coord;
+use input::*;
+use state::State;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy)]
+enum Destination {
+    Coord(Coord),
+    // the nearest unexplored cell, chosen again at each step
+    Unexplored,
+}
+
+// Walks the player to a destination one step at a time. Travel stops when an
+// npc which wasn't already visible comes into view, or the player takes
+// damage. Frontends should also stop travelling when `tick` returns an event,
+// such as an alert.
+#[derive(Debug, Clone)]
+pub struct Travel {
+    destination: Destination,
+    visible_npcs: HashSet<EntityId>,
+    damage_taken: u32,
+    explored: bool,
+    path: Vec<Direction>,
+}
+
+impl Travel {
+    fn with_destination(destination: Destination, state: &State) -> Self {
+        Self {
+            destination,
+            visible_npcs: state.visible_npcs(),
+            damage_taken: state.stats().damage_taken,
+            explored: false,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn new(destination: Coord, state: &State) -> Self {
+        Self::with_destination(Destination::Coord(destination), state)
+    }
+
+    // Repeatedly walks to the nearest unexplored cell or unvisited pickup
+    pub fn explore(state: &State) -> Self {
+        Self::with_destination(Destination::Unexplored, state)
+    }
+
+    // true if exploring stopped because there was nowhere left to explore
+    pub fn is_explored(&self) -> bool {
+        self.explored
+    }
+
+    // true if a new npc has come into view or the player has taken damage
+    // since the last step
+    fn is_interrupted(&mut self, visible_npcs: HashSet<EntityId>, damage_taken: u32) -> bool {
+        if !visible_npcs.is_subset(&self.visible_npcs) {
+            return true;
+        }
+        self.visible_npcs = visible_npcs;
+        damage_taken > self.damage_taken
+    }
+
+    fn set_explored(&mut self, found: bool) {
+        // visible npcs may be in the way of the rest of the level
+        self.explored = !found && self.visible_npcs.is_empty();
+    }
+
+    // The next input to pass to the game, or None if travel is over. Call this
+    // when the game is awaiting input. The path is recomputed at each step, as
+    // the player learns more about the level along the way.
+    pub fn next_input(&mut self, state: &mut State) -> Option<Input> {
+        if self.is_interrupted(state.visible_npcs(), state.stats().damage_taken) {
+            return None;
+        }
+        if state.selected_meter_type().is_some() {
+            // directional inputs would use the selected meter
+            return None;
+        }
+        let found = match self.destination {
+            Destination::Coord(coord) => state.travel_path(coord, &mut self.path),
+            Destination::Unexplored => {
+                let found = state.explore_path(&mut self.path);
+                self.set_explored(found);
+                found
+            }
+        };
+        if !found {
+            return None;
+        }
+        self.path
+            .first()
+            .and_then(|direction| direction.cardinal())
+            .map(Input::Direction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn start(destination: Destination, visible_npcs: &[EntityId]) -> Travel {
+        Travel {
+            destination,
+            visible_npcs: visible_npcs.iter().cloned().collect(),
+            damage_taken: 0,
+            explored: false,
+            path: Vec::new(),
+        }
+    }
+
+    fn npcs(ids: &[EntityId]) -> HashSet<EntityId> {
+        ids.iter().cloned().collect()
+    }
+
+    #[test]
+    fn new_npcs_interrupt_travel() {
+        let mut travel = start(Destination::Coord(Coord::new(0, 0)), &[1, 2]);
+        assert!(!travel.is_interrupted(npcs(&[1, 2]), 0));
+        assert!(!travel.is_interrupted(npcs(&[1]), 0));
+        // npc 2 went out of view, so seeing it again is a new sighting
+        assert!(travel.is_interrupted(npcs(&[1, 2]), 0));
+        let mut travel = start(Destination::Coord(Coord::new(0, 0)), &[]);
+        assert!(travel.is_interrupted(npcs(&[3]), 0));
+    }
+
+    #[test]
+    fn damage_interrupts_travel() {
+        let mut travel = start(Destination::Coord(Coord::new(0, 0)), &[]);
+        assert!(!travel.is_interrupted(npcs(&[]), 0));
+        assert!(travel.is_interrupted(npcs(&[]), 1));
+    }
+
+    #[test]
+    fn explored_only_without_visible_npcs() {
+        let mut travel = start(Destination::Unexplored, &[]);
+        travel.set_explored(true);
+        assert!(!travel.is_explored());
+        travel.set_explored(false);
+        assert!(travel.is_explored());
+        let mut travel = start(Destination::Unexplored, &[1]);
+        travel.set_explored(false);
+        assert!(!travel.is_explored());
+    }
+}
@@ -1 +1,50 @@
+use noise;
+use stats::DamageSource;
+
 pub const GUN_BULLET_RANGE: u32 = 5;
+pub const SPIT_RANGE: u32 = 6;
+pub const SPITTER_MIN_DISTANCE: u32 = 3;
+pub const GRENADE_RANGE: u32 = 5;
+pub const GRENADE_FUSE: i32 = 3;
+pub const GRENADE_RADIUS: i32 = 2;
+pub const GRENADE_DAMAGE: i32 = 2;
+pub const CRUSH_DAMAGE: i32 = 1;
+pub const FLARE_RADIUS: i32 = 12;
+pub const FLARE_TURNS: i32 = 8;
+pub const DECOY_HEALTH: i32 = 4;
+pub const DECOY_TURNS: i32 = 20;
+pub const DECOY_RANGE: u32 = 16;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ExplosionInfo {
+    pub damage: i32,
+    pub source: DamageSource,
+}
+
+// Damage dealt to everything within `radius` of a point. Damage decreases by
+// `falloff` per cell of distance from the centre, and if `line_of_effect` is
+// set, opaque cells shield the cells behind them.
+#[derive(Debug, Clone, Copy)]
+pub struct AreaDamage {
+    pub radius: i32,
+    pub damage: i32,
+    pub falloff: i32,
+    pub line_of_effect: bool,
+    pub noise_radius: u32,
+}
+
+pub const GRENADE_EXPLOSION: AreaDamage = AreaDamage {
+    radius: GRENADE_RADIUS,
+    damage: GRENADE_DAMAGE,
+    falloff: 0,
+    line_of_effect: true,
+    noise_radius: noise::GRENADE_NOISE_RADIUS,
+};
+
+pub const VOLATILE_EGG_EXPLOSION: AreaDamage = AreaDamage {
+    radius: 2,
+    damage: 3,
+    falloff: 1,
+    line_of_effect: true,
+    noise_radius: noise::GRENADE_NOISE_RADIUS,
+};
@@ -0,0 +1,73 @@
+use meters::damage::*;
+use meters::tile::*;
+use prototty::*;
+use std::fmt::Write;
+
+use super::glossary;
+
+const COMBAT_LOG_WIDTH: usize = 30;
+
+pub struct CombatLogView {
+    scratch: String,
+}
+
+impl CombatLogView {
+    pub fn new() -> Self {
+        Self {
+            scratch: String::new(),
+        }
+    }
+}
+
+pub fn damage_type_name(typ: DamageType) -> &'static str {
+    match typ {
+        DamageType::Kinetic => "kinetic",
+        DamageType::Piercing => "piercing",
+        DamageType::Blast => "blast",
+        DamageType::Chemical => "chemical",
+    }
+}
+
+fn hit_target_name(tile: Tile) -> &'static str {
+    match tile {
+        Tile::Player => "You",
+        _ => glossary::tile_name(tile),
+    }
+}
+
+fn write_hit(stage: &mut String, hit: &Hit) {
+    write!(
+        stage,
+        "{} took {} {}",
+        hit_target_name(hit.tile),
+        hit.dealt,
+        damage_type_name(hit.damage.typ)
+    )
+    .unwrap();
+    if hit.modifier < 0 {
+        write!(stage, " (resist)").unwrap();
+    } else if hit.modifier > 0 {
+        write!(stage, " (weak)").unwrap();
+    }
+}
+
+fn hit_text_info(hit: &Hit) -> TextInfo {
+    if hit.tile == Tile::Player {
+        TextInfo::default().foreground_colour(Rgb24::new(255, 63, 63))
+    } else if hit.modifier > 0 {
+        TextInfo::default().foreground_colour(Rgb24::new(255, 255, 0))
+    } else if hit.modifier < 0 {
+        TextInfo::default().foreground_colour(Rgb24::new(127, 127, 127))
+    } else {
+        TextInfo::default().foreground_colour(Rgb24::new(255, 255, 255))
+    }
+}
+
+impl View<Hit> for CombatLogView {
+    fn view<G: ViewGrid>(&mut self, hit: &Hit, offset: Coord, depth: i32, grid: &mut G) {
+        self.scratch.clear();
+        write_hit(&mut self.scratch, hit);
+        let text: String = self.scratch.chars().take(COMBAT_LOG_WIDTH).collect();
+        TextInfoStringView.view(&(hit_text_info(hit), &text), offset, depth, grid);
+    }
+}
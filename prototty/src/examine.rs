@@ -0,0 +1,119 @@
+use meters::state::Visibility;
+use meters::tile::*;
+use meters::tile_info::*;
+use meters::transform::Transform;
+use prototty::*;
+use std::fmt::Write;
+use std::slice;
+
+use super::glossary;
+use super::meter;
+use super::render;
+
+// the remaining lines of the area below the map are used for the description
+const MAX_LINES: usize = 4;
+
+pub struct ExamineView {
+    scratch: String,
+    tiles: Vec<TileInfo>,
+}
+
+impl ExamineView {
+    pub fn new() -> Self {
+        Self {
+            scratch: String::new(),
+            tiles: Vec::new(),
+        }
+    }
+}
+
+fn transform_name(transform: Transform) -> &'static str {
+    match transform {
+        Transform::Queen => "Queen",
+        Transform::Aracnoid => "Aracnoid",
+        Transform::Beetoid => "Beetoid",
+        Transform::Chrysalis => "Chrysalis",
+        Transform::Larvae => "Larvae",
+    }
+}
+
+fn pickup_description(tile: Tile) -> Option<&'static str> {
+    match tile {
+        Tile::HealthPickup => Some("restores health"),
+        Tile::AmmoPickup => Some("refills quadgun ammo"),
+        Tile::RailGunAmmoPickup => Some("refills railgun ammo"),
+        Tile::MetabolAmmoPickup => Some("refills metabol ammo"),
+        Tile::PushAmmoPickup => Some("refills push ammo"),
+        Tile::GrenadeAmmoPickup => Some("refills grenades"),
+        Tile::FlareAmmoPickup => Some("refills flares"),
+        Tile::KevlarPickup => Some("restores armour"),
+        _ => None,
+    }
+}
+
+fn write_description(stage: &mut String, tile_info: TileInfo) {
+    write!(stage, "{}", glossary::tile_name(tile_info.tile)).unwrap();
+    if tile_info.boss {
+        write!(stage, " (boss)").unwrap();
+    }
+    if let Some(description) = pickup_description(tile_info.tile) {
+        write!(stage, ": {}", description).unwrap();
+        return;
+    }
+    if tile_info.tile == Tile::Player {
+        return;
+    }
+    if let Some(health_meter) = tile_info.health_meter {
+        write!(stage, ": {}/{}hp", health_meter.value, health_meter.max).unwrap();
+    }
+    if let Some(transform) = tile_info.transform {
+        write!(stage, ", becomes {}", transform_name(transform)).unwrap();
+        if let Some(countdown) = tile_info.countdown {
+            write!(stage, " in {} turns", countdown).unwrap();
+        }
+    }
+    for typ in tile_info.status_effects.iter() {
+        write!(stage, " ({})", meter::status_effect_name(typ)).unwrap();
+    }
+}
+
+impl<'a> View<Option<(slice::Iter<'a, TileInfo>, Visibility)>> for ExamineView {
+    fn view<G: ViewGrid>(
+        &mut self,
+        cell: &Option<(slice::Iter<'a, TileInfo>, Visibility)>,
+        offset: Coord,
+        depth: i32,
+        grid: &mut G,
+    ) {
+        let visibility = if let Some((ref tiles, visibility)) = *cell {
+            self.tiles.clear();
+            self.tiles.extend(tiles.clone().filter(|tile_info| {
+                // only describe the parts of remembered cells which are drawn
+                visibility == Visibility::Visible
+                    || render::render_when_non_visible(tile_info.tile)
+                    || (tile_info.health_meter.is_some() && tile_info.tile != Tile::Player)
+            }));
+            self.tiles.sort_by_key(|tile_info| -tile_info.depth);
+            visibility
+        } else {
+            StringView.view("You haven't seen this place.", offset, depth, grid);
+            return;
+        };
+        let title = match visibility {
+            Visibility::Visible => "You see:",
+            Visibility::Remembered => "You remember:",
+        };
+        StringView.view(title, offset, depth, grid);
+        for (y, &tile_info) in self.tiles.iter().take(MAX_LINES).enumerate() {
+            let (_, info) = render::tile_text(tile_info);
+            self.scratch.clear();
+            write_description(&mut self.scratch, tile_info);
+            TextInfoStringView.view(
+                &(info, &self.scratch),
+                offset + Coord::new(0, y as i32 + 1),
+                depth,
+                grid,
+            );
+        }
+    }
+}
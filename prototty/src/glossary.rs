@@ -4,10 +4,29 @@ use prototty::*;
 use std::collections::BTreeSet;
 use std::fmt::Write;
 
+use super::meter;
 use super::render;
 
 const GLOSSARY_WIDTH: i32 = 60;
 
+pub struct Glossary {
+    pub visible: BTreeSet<TileInfo>,
+    pub remembered: BTreeSet<TileInfo>,
+}
+
+impl Glossary {
+    pub fn new() -> Self {
+        Self {
+            visible: BTreeSet::new(),
+            remembered: BTreeSet::new(),
+        }
+    }
+    pub fn clear(&mut self) {
+        self.visible.clear();
+        self.remembered.clear();
+    }
+}
+
 pub struct GlossaryView {
     scratch: String,
 }
@@ -20,45 +39,83 @@ impl GlossaryView {
     }
 }
 
-fn write_tile(stage: &mut String, ch: char, tile_info: TileInfo) -> bool {
-    match tile_info.tile {
-        Tile::Player => write!(stage, "{} {}", ch, "Player"),
-        Tile::Egg => write!(stage, "{} {}", ch, "Egg"),
-        Tile::Larvae => write!(stage, "{} {}", ch, "Larvae"),
-        Tile::Chrysalis => write!(stage, "{} {}", ch, "Chrysalis"),
-        Tile::Aracnoid => write!(stage, "{} {}", ch, "Aracnoid"),
-        Tile::Beetoid => write!(stage, "{} {}", ch, "Beetoid"),
-        Tile::SuperEgg => write!(stage, "{} {}", ch, "Super Egg"),
-        Tile::Queen => write!(stage, "{} {}", ch, "Queen"),
-        Tile::Stairs => write!(stage, "{} {}", ch, "Stairs"),
-        Tile::Exit => write!(stage, "{} {}", ch, "Exit"),
-        Tile::HealthPickup => write!(stage, "{} {}", ch, "Meds"),
-        Tile::AmmoPickup => write!(stage, "{} {}", ch, "Quadgun Ammo"),
-        Tile::RailGunAmmoPickup => write!(stage, "{} {}", ch, "Railgun Ammo"),
-        Tile::MetabolAmmoPickup => write!(stage, "{} {}", ch, "Metabol Ammo"),
-        Tile::PushAmmoPickup => write!(stage, "{} {}", ch, "Push Ammo"),
-        Tile::KevlarPickup => write!(stage, "{} {}", ch, "Armour Shard"),
-        Tile::BeaconInactive => write!(stage, "{} {}", ch, "Beacon (inactive)"),
-        Tile::BeaconActive => write!(stage, "{} {}", ch, "Beacon (active)"),
+pub fn tile_name(tile: Tile) -> &'static str {
+    match tile {
+        Tile::Player => "Player",
+        Tile::Decoy => "Decoy",
+        Tile::Shield(_) => "Shield",
+        Tile::Egg => "Egg",
+        Tile::VolatileEgg => "Volatile Egg",
+        Tile::Larvae => "Larvae",
+        Tile::Chrysalis => "Chrysalis",
+        Tile::Aracnoid => "Aracnoid",
+        Tile::Beetoid => "Beetoid",
+        Tile::Spitter => "Spitter",
+        Tile::SuperEgg => "Super Egg",
+        Tile::Queen => "Queen",
+        Tile::Stairs => "Stairs",
+        Tile::Exit => "Exit",
+        Tile::HealthPickup => "Meds",
+        Tile::AmmoPickup => "Quadgun Ammo",
+        Tile::RailGunAmmoPickup => "Railgun Ammo",
+        Tile::MetabolAmmoPickup => "Metabol Ammo",
+        Tile::PushAmmoPickup => "Push Ammo",
+        Tile::GrenadeAmmoPickup => "Grenades",
+        Tile::Grenade => "Grenade",
+        Tile::FlareAmmoPickup => "Flares",
+        Tile::Flare => "Flare",
+        Tile::KevlarPickup => "Armour Shard",
+        Tile::BeaconInactive => "Beacon (inactive)",
+        Tile::BeaconActive => "Beacon (active)",
+        Tile::Wall => "Wall",
+        Tile::CavernWall => "Cavern Wall",
+        Tile::Door => "Door",
+        Tile::Floor => "Floor",
+        Tile::Punch(_) => "Punch",
+        Tile::Bullet => "Bullet",
+        Tile::Spit => "Spit",
+        Tile::Explosion => "Explosion",
+        Tile::RailGunShotHorizontal | Tile::RailGunShotVertical => "Railgun Shot",
+        Tile::MetabolWave => "Metabol Wave",
+        Tile::PushWave => "Push Wave",
+    }
+}
+
+fn in_glossary(tile: Tile) -> bool {
+    !matches!(
+        tile,
         Tile::Wall
-        | Tile::CavernWall
-        | Tile::Door
-        | Tile::Floor
-        | Tile::Punch(_)
-        | Tile::Bullet
-        | Tile::RailGunShotHorizontal
-        | Tile::MetabolWave
-        | Tile::PushWave
-        | Tile::RailGunShotVertical => return false,
+            | Tile::CavernWall
+            | Tile::Door
+            | Tile::Floor
+            | Tile::Punch(_)
+            | Tile::Bullet
+            | Tile::Spit
+            | Tile::Explosion
+            | Tile::RailGunShotHorizontal
+            | Tile::MetabolWave
+            | Tile::PushWave
+            | Tile::RailGunShotVertical
+    )
+}
+
+fn write_tile(stage: &mut String, ch: char, tile_info: TileInfo, remembered: bool) -> bool {
+    if !in_glossary(tile_info.tile) {
+        return false;
+    }
+    write!(stage, "{} {}", ch, tile_name(tile_info.tile)).unwrap();
+
+    if remembered {
+        write!(stage, " (remembered)").unwrap();
+        return true;
     }
-    .unwrap();
 
     if tile_info.boss {
         write!(stage, " (boss)").unwrap();
     } else {
         if let Some(1) = tile_info.countdown {
             match tile_info.tile {
-                Tile::Egg | Tile::Chrysalis | Tile::SuperEgg => {
+                Tile::Egg | Tile::VolatileEgg | Tile::Chrysalis | Tile::SuperEgg => {
                     write!(stage, " (hatching)").unwrap();
                     return true;
                 }
@@ -69,37 +126,30 @@ fn write_tile(stage: &mut String, ch: char, tile_info: TileInfo) -> bool {
         if let Some(health_meter) = tile_info.health_meter {
             if tile_info.tile != Tile::Player && health_meter.value < health_meter.max {
                 write!(stage, " ({}hp)", health_meter.value).unwrap();
-                return true;
             }
         }
+    }
 
-        if tile_info.delayed_transform {
-            match tile_info.tile {
-                Tile::SuperEgg | Tile::Chrysalis | Tile::Egg | Tile::Larvae => {
-                    write!(stage, " (transform delayed)").unwrap();
-                    return true;
-                }
-                _ => (),
-            }
-        }
+    for typ in tile_info.status_effects.iter() {
+        write!(stage, " ({})", meter::status_effect_name(typ)).unwrap();
     }
 
     true
 }
 
-impl View<BTreeSet<TileInfo>> for GlossaryView {
-    fn view<G: ViewGrid>(
-        &mut self,
-        glossary: &BTreeSet<TileInfo>,
-        offset: Coord,
-        depth: i32,
-        grid: &mut G,
-    ) {
+impl View<Glossary> for GlossaryView {
+    fn view<G: ViewGrid>(&mut self, glossary: &Glossary, offset: Coord, depth: i32, grid: &mut G) {
         let mut coord = Coord::new(0, 0);
-        for &tile_info in glossary.iter() {
+        let visible = glossary.visible.iter().map(|&tile_info| (tile_info, false));
+        let remembered = glossary
+            .remembered
+            .iter()
+            .filter(|tile_info| !glossary.visible.contains(tile_info))
+            .map(|&tile_info| (tile_info, true));
+        for (tile_info, remembered) in visible.chain(remembered) {
             let (ch, info) = render::tile_text(tile_info);
             self.scratch.clear();
-            if write_tile(&mut self.scratch, ch, tile_info) {
+            if write_tile(&mut self.scratch, ch, tile_info, remembered) {
                 let len = self.scratch.chars().count() as i32 + 2;
                 let mut next_x = coord.x + len;
                 if next_x > GLOSSARY_WIDTH {
@@ -11,14 +11,17 @@ use direction::CardinalDirection;
 use meters::alert::*;
 use meters::input::ActiveMeterIdentifier;
 use meters::input::Input as MetersInput;
+use meters::loadout::*;
 use meters::meter::*;
 use meters::state::*;
+use meters::stats::Stats;
+use meters::status::StatusEffectType;
 use meters::tile_info::TileInfo;
+use meters::travel::Travel;
 use meters::*;
 use prototty::*;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
-use std::collections::BTreeSet;
 use std::time::Duration;
 
 use self::CardinalDirection::*;
@@ -32,10 +35,20 @@ use self::goal::*;
 mod glossary;
 use self::glossary::*;
 
+mod loadout;
+use self::loadout::*;
+
+mod combat_log;
+use self::combat_log::*;
+
+mod examine;
+use self::examine::*;
+
 mod render;
 
 const SAVE_PERIOD_MS: u64 = 10000;
 const SAVE_FILE: &'static str = "save";
+const LOADOUT_FILE: &str = "loadout";
 
 const GAME_OVER_MS: u64 = 4000;
 const GAME_WIDTH: u32 = 29;
@@ -52,21 +65,35 @@ const OVERALL_PROGRESS_METER_WIDTH: usize = 29;
 const ACTIVE_METER_Y: i32 = 5;
 const NUM_ACTIVE_METERS: i32 = 10;
 const NUM_PASSIVE_METERS: i32 = 10;
+const NUM_STATUS_EFFECTS: i32 = 4;
 
 const GOAL_TEXT_Y: i32 = 1;
 const GOAL_METER_BOTTOM_Y: i32 = 29;
 
+const COMBAT_LOG_TOP_Y: i32 = 31;
+const NUM_COMBAT_LOG_ENTRIES: i32 = 5;
+
 const GLOSSARY_TOP_Y: i32 = 36;
 
 const MESSAGE_TOP_Y: i32 = 3;
 const HELP_TOP_Y: i32 = 43;
 
-const HELP: &'static str = "Move:↑←↑→  Wait:SPACE  Ability:0-9  Menu:ESC";
+const HELP: &'static str = "Move:↑←↑→ Wait:SPACE Ability:0-9 Loadout:L Look:X Menu:ESC";
+const TRAVEL_HELP: &str = "Explore:O Travel:LEFT CLICK Look:RIGHT CLICK";
+const EXAMINE_HELP: &str = "Move Cursor:↑←↑→  Back:X/ESC";
+
+const EXAMINE_CURSOR_DEPTH: i32 = 100;
+const MOUSE_HOVER_BACKGROUND: Rgb24 = Rgb24 {
+    red: 63,
+    green: 63,
+    blue: 95,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct FullSaveState {
     game: SaveState,
     between_level_menu: Option<MenuInstance<BetweenLevelChoice>>,
+    upgrade_choices: Vec<MeterType>,
     game_state: GameState,
 }
 
@@ -86,14 +113,28 @@ impl Frontend {
     }
 }
 
-fn colour_cell(fg: Option<Rgb24>, bg: Option<Rgb24>, visible: bool) -> ViewCell {
+// visible cells are drawn darker the less light they receive, down to this
+// fraction (out of 255) of their full brightness
+const MIN_LIGHT_SCALE: u32 = 96;
+
+fn colour_cell(fg: Option<Rgb24>, bg: Option<Rgb24>, visible: bool, light: u8) -> ViewCell {
     let mut cell = ViewCell::new();
     if visible {
-        if let Some(fg) = fg {
-            cell.set_foreground(fg);
+        let scale = MIN_LIGHT_SCALE + ((255 - MIN_LIGHT_SCALE) * light as u32) / 255;
+        let l = |c| ((c as u32 * scale) / 255) as u8;
+        if let Some(Rgb24 { red, green, blue }) = fg {
+            cell.set_foreground(Rgb24 {
+                red: l(red),
+                green: l(green),
+                blue: l(blue),
+            });
         }
-        if let Some(bg) = bg {
-            cell.set_background(bg);
+        if let Some(Rgb24 { red, green, blue }) = bg {
+            cell.set_background(Rgb24 {
+                red: l(red),
+                green: l(green),
+                blue: l(blue),
+            });
         }
     } else {
         let b = |c| c / 8;
@@ -116,7 +157,7 @@ fn colour_cell(fg: Option<Rgb24>, bg: Option<Rgb24>, visible: bool) -> ViewCell
     cell
 }
 
-fn view_tile(tile_info: TileInfo, visibility: Visibility) -> ViewCell {
+fn view_tile(tile_info: TileInfo, visibility: Visibility, light: u8) -> ViewCell {
     let visible = match visibility {
         Visibility::Visible => true,
         Visibility::Remembered => false,
@@ -125,7 +166,12 @@ fn view_tile(tile_info: TileInfo, visibility: Visibility) -> ViewCell {
         return Default::default();
     }
     let (ch, info) = render::tile_text(tile_info);
-    let mut cell = colour_cell(info.foreground_colour, info.background_colour, visible);
+    let mut cell = colour_cell(
+        info.foreground_colour,
+        info.background_colour,
+        visible,
+        light,
+    );
     cell.set_character(ch);
     if info.bold {
         cell.set_bold(true);
@@ -133,6 +179,25 @@ fn view_tile(tile_info: TileInfo, visibility: Visibility) -> ViewCell {
     cell
 }
 
+// npcs remembered from when their cell was last visible are drawn like the
+// rest of the remembered map
+fn view_ghost(tile_info: TileInfo) -> ViewCell {
+    let (ch, info) = render::tile_text(tile_info);
+    let mut cell = colour_cell(info.foreground_colour, info.background_colour, false, 0);
+    cell.set_character(ch);
+    cell
+}
+
+fn glossary_entry(mut tile_info: TileInfo) -> TileInfo {
+    if let Some(countdown) = tile_info.countdown.as_mut() {
+        *countdown = ::std::cmp::min(*countdown, 2); // fuck
+    }
+    tile_info.status_effects.remove(StatusEffectType::Pushed);
+    // the examine view is used to see what a particular npc is turning into
+    tile_info.transform = None;
+    tile_info
+}
+
 const INITIAL_INPUT_BUFFER_SIZE: usize = 16;
 
 #[derive(Debug, Clone, Copy)]
@@ -145,6 +210,7 @@ enum GameOverMessage {
 enum GameState {
     Level,
     UpgradeMenu,
+    DiscardMenu,
     NonUpgradeMenu,
 }
 
@@ -153,6 +219,8 @@ enum AppState {
     Game,
     GameOver(GameOverMessage),
     MainMenu,
+    Loadout,
+    Examine,
 }
 
 pub enum ControlFlow {
@@ -191,11 +259,14 @@ impl GameMenuView {
 pub struct AppView {
     title_screen_view: Decorated<GameMenuView, Align>,
     between_level_view: Decorated<GameMenuView, Align>,
+    loadout_view: Decorated<LoadoutView, Align>,
     meter_view: MeterView,
     overall_progress_view: MeterView,
     goal_view: GoalView,
     glossary_view: GlossaryView,
-    glossary: BTreeSet<TileInfo>,
+    combat_log_view: CombatLogView,
+    examine_view: ExamineView,
+    glossary: Glossary,
 }
 
 impl<'a, T: Copy> View<(&'static str, &'a MenuInstance<T>)> for GameMenuView {
@@ -227,6 +298,7 @@ impl AppView {
         Self {
             title_screen_view: Decorated::new(GameMenuView::new(info), align),
             between_level_view: Decorated::new(GameMenuView::new(Default::default()), align),
+            loadout_view: Decorated::new(LoadoutView::new(), align),
             meter_view: MeterView::new(METER_NAME_PADDING, METER_WIDTH),
             overall_progress_view: MeterView::new(
                 OVERALL_PROGRESS_METER_NAME_PADDING,
@@ -234,23 +306,31 @@ impl AppView {
             ),
             goal_view: GoalView::new(),
             glossary_view: GlossaryView::new(),
-            glossary: BTreeSet::new(),
+            combat_log_view: CombatLogView::new(),
+            examine_view: ExamineView::new(),
+            glossary: Glossary::new(),
         }
     }
     pub fn set_size(&mut self, size: Size) {
         self.title_screen_view.decorator.size = size;
+        self.loadout_view.decorator.size = size;
     }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 enum BetweenLevelChoice {
     Upgrade(MeterType),
+    ChooseDiscard,
+    Discard(MeterType),
+    Swap(MeterType, MeterType),
+    Back,
     Continue,
 }
 
 pub struct App<S: Storage> {
     main_menu: MenuInstance<MainMenuChoice>,
     between_level_menu: Option<MenuInstance<BetweenLevelChoice>>,
+    upgrade_choices: Vec<MeterType>,
     app_state: AppState,
     game_state: GameState,
     state: State,
@@ -262,6 +342,10 @@ pub struct App<S: Storage> {
     frontend: Frontend,
     save_remaining: Duration,
     alert: Option<Alert>,
+    loadout_cursor: usize,
+    examine_cursor: Coord,
+    mouse_coord: Option<Coord>,
+    travel: Option<Travel>,
 }
 
 impl<S: Storage> View<App<S>> for AppView {
@@ -275,7 +359,7 @@ impl<S: Storage> View<App<S>> for AppView {
                     grid,
                 );
             }
-            AppState::Game => match app.game_state {
+            AppState::Game | AppState::Examine => match app.game_state {
                 GameState::Level => {
                     self.goal_view.view(
                         &app.state.goal_info(),
@@ -285,28 +369,31 @@ impl<S: Storage> View<App<S>> for AppView {
                     );
 
                     self.glossary.clear();
-                    for (tiles, coord, visibility) in app.state.visible_cells() {
+                    for (tiles, coord, visibility, light) in app.state.visible_cells() {
                         for tile_info in tiles {
                             grid.set_cell(
                                 offset + Coord::new(coord.x, coord.y + GAME_TOP_PADDING),
                                 tile_info.depth + depth,
                                 {
-                                    let cell = view_tile(*tile_info, visibility);
+                                    let cell = view_tile(*tile_info, visibility, light);
                                     if visibility == Visibility::Visible
                                         || render::render_when_non_visible(tile_info.tile)
                                     {
-                                        let mut tile_info = *tile_info;
-                                        if let Some(countdown) = tile_info.countdown.as_mut() {
-                                            *countdown = ::std::cmp::min(*countdown, 2); // fuck
-                                        }
-                                        tile_info.pushed = false;
-                                        self.glossary.insert(tile_info);
+                                        self.glossary.visible.insert(glossary_entry(*tile_info));
                                     }
                                     cell
                                 },
                             );
                         }
                     }
+                    for (coord, tile_info) in app.state.remembered_npcs() {
+                        grid.set_cell(
+                            offset + Coord::new(coord.x, coord.y + GAME_TOP_PADDING),
+                            tile_info.depth + depth,
+                            view_ghost(tile_info),
+                        );
+                        self.glossary.remembered.insert(glossary_entry(tile_info));
+                    }
 
                     let mut active_end = 0;
                     let active_meter_offset = offset
@@ -328,6 +415,7 @@ impl<S: Storage> View<App<S>> for AppView {
                             GAME_WIDTH as i32 + 1,
                             GAME_TOP_PADDING + ACTIVE_METER_Y + active_end,
                         );
+                    let mut passive_end = 0;
                     for (y, info) in
                         izip!(0..NUM_PASSIVE_METERS, app.state.player_passive_meter_info())
                     {
@@ -337,6 +425,19 @@ impl<S: Storage> View<App<S>> for AppView {
                             depth,
                             grid,
                         );
+                        passive_end += 1;
+                    }
+
+                    let status_effect_offset =
+                        passive_meter_offset + Coord::new(0, passive_end + 1);
+                    let status_effects = app.state.player_status_effects();
+                    for (y, info) in izip!(0..NUM_STATUS_EFFECTS, status_effects.iter()) {
+                        self.meter_view.view(
+                            &info,
+                            status_effect_offset + Coord::new(0, y),
+                            depth,
+                            grid,
+                        );
                     }
 
                     let mut from_bottom = 0;
@@ -351,6 +452,17 @@ impl<S: Storage> View<App<S>> for AppView {
                         from_bottom += 1;
                     });
 
+                    let combat_log_offset =
+                        offset + Coord::new(GAME_WIDTH as i32 + 1, COMBAT_LOG_TOP_Y);
+                    for (y, hit) in izip!(0..NUM_COMBAT_LOG_ENTRIES, app.state.combat_log()) {
+                        self.combat_log_view.view(
+                            hit,
+                            combat_log_offset + Coord::new(0, y),
+                            depth,
+                            grid,
+                        );
+                    }
+
                     let overall_progress_offset = offset + Coord::new(0, OVERALL_PROGRESS_Y);
                     const OVERALL_PROGRESS_TITLE: &'static str = "Metres Below the Ground";
                     let overall_progress_meter = app.state.overall_progress_meter();
@@ -362,8 +474,32 @@ impl<S: Storage> View<App<S>> for AppView {
                     );
 
                     let glossary_offset = offset + Coord::new(0, GLOSSARY_TOP_Y);
-                    self.glossary_view
-                        .view(&self.glossary, glossary_offset, depth, grid);
+                    if let AppState::Examine = app.app_state {
+                        let cursor = app.examine_cursor;
+                        grid.set_cell(
+                            offset + Coord::new(cursor.x, cursor.y + GAME_TOP_PADDING),
+                            depth + EXAMINE_CURSOR_DEPTH,
+                            ViewCell::new()
+                                .with_foreground(colours::BLACK)
+                                .with_background(colours::WHITE),
+                        );
+                        self.examine_view.view(
+                            &app.state.known_cell(cursor),
+                            glossary_offset,
+                            depth,
+                            grid,
+                        );
+                    } else {
+                        if let Some(coord) = app.mouse_coord {
+                            grid.set_cell(
+                                offset + Coord::new(coord.x, coord.y + GAME_TOP_PADDING),
+                                depth + EXAMINE_CURSOR_DEPTH,
+                                ViewCell::new().with_background(MOUSE_HOVER_BACKGROUND),
+                            );
+                        }
+                        self.glossary_view
+                            .view(&self.glossary, glossary_offset, depth, grid);
+                    }
 
                     if let Some(alert) = app.alert {
                         TextInfoStringView.view(
@@ -373,7 +509,20 @@ impl<S: Storage> View<App<S>> for AppView {
                             grid,
                         );
                     }
-                    StringView.view(HELP, offset + Coord::new(0, HELP_TOP_Y), depth, grid);
+                    let help = if let AppState::Examine = app.app_state {
+                        EXAMINE_HELP
+                    } else {
+                        HELP
+                    };
+                    StringView.view(help, offset + Coord::new(0, HELP_TOP_Y), depth, grid);
+                    if let AppState::Game = app.app_state {
+                        StringView.view(
+                            TRAVEL_HELP,
+                            offset + Coord::new(0, HELP_TOP_Y + 1),
+                            depth,
+                            grid,
+                        );
+                    }
                 }
                 GameState::UpgradeMenu => {
                     if let Some(menu) = app.between_level_menu.as_ref() {
@@ -385,6 +534,16 @@ impl<S: Storage> View<App<S>> for AppView {
                         );
                     }
                 }
+                GameState::DiscardMenu => {
+                    if let Some(menu) = app.between_level_menu.as_ref() {
+                        self.between_level_view.view(
+                            &("Choose a meter to discard:", menu),
+                            offset,
+                            depth,
+                            grid,
+                        );
+                    }
+                }
                 GameState::NonUpgradeMenu => {
                     if let Some(menu) = app.between_level_menu.as_ref() {
                         self.between_level_view.view(
@@ -396,56 +555,99 @@ impl<S: Storage> View<App<S>> for AppView {
                     }
                 }
             },
-            AppState::GameOver(message) => match message {
-                GameOverMessage::Lose => {
-                    Decorated::new(
-                        TextInfoStringView,
-                        Align::new(
-                            self.title_screen_view.decorator.size,
-                            Alignment::Centre,
-                            Alignment::Centre,
-                        ),
-                    )
-                    .view(
-                        &(
-                            TextInfo::default().bold().foreground_colour(colours::RED),
-                            "You Died",
-                        ),
-                        offset,
-                        depth,
-                        grid,
-                    );
-                }
-                GameOverMessage::Win => {
-                    Decorated::new(
-                        TextInfoStringView,
-                        Align::new(
-                            self.title_screen_view.decorator.size,
-                            Alignment::Centre,
-                            Alignment::Centre,
-                        ),
-                    )
-                    .view(
-                        &(
-                            TextInfo::default().bold().foreground_colour(colours::GREEN),
-                            "You Escaped",
-                        ),
-                        offset,
+            AppState::Loadout => {
+                self.loadout_view.view(
+                    &(app.state.loadout(), app.loadout_cursor),
+                    offset,
+                    depth,
+                    grid,
+                );
+            }
+            AppState::GameOver(message) => {
+                let text = match message {
+                    GameOverMessage::Lose => (
+                        TextInfo::default().bold().foreground_colour(colours::RED),
+                        "You Died",
+                    ),
+                    GameOverMessage::Win => (
+                        TextInfo::default().bold().foreground_colour(colours::GREEN),
+                        "You Escaped",
+                    ),
+                };
+                let align = Align::new(
+                    self.title_screen_view.decorator.size,
+                    Alignment::Centre,
+                    Alignment::Centre,
+                );
+                Decorated::new(TextInfoStringView, align).view(&text, offset, depth, grid);
+                // a summary of the run is listed below the message
+                for (i, line) in stats_lines(app.state.stats()).iter().enumerate() {
+                    Decorated::new(StringView, align).view(
+                        line,
+                        offset + Coord::new(0, 2 + i as i32),
                         depth,
                         grid,
                     );
                 }
-            },
+            }
         }
     }
 }
 
-fn make_upgrade_menu(upgrades: Vec<MeterType>) -> MenuInstance<BetweenLevelChoice> {
-    let items = upgrades
+fn make_upgrade_menu(
+    upgrades: &[MeterType],
+    can_discard: bool,
+) -> MenuInstance<BetweenLevelChoice> {
+    let mut items = upgrades
         .iter()
-        .map(|&typ| (meter::meter_name(typ), BetweenLevelChoice::Upgrade(typ)))
+        .map(|&typ| {
+            (
+                meter::meter_name(typ).to_string(),
+                BetweenLevelChoice::Upgrade(typ),
+            )
+        })
         .collect::<Vec<_>>();
 
+    if can_discard {
+        items.push((
+            "Swap out an existing meter...".to_string(),
+            BetweenLevelChoice::ChooseDiscard,
+        ));
+    }
+
+    let menu = Menu::smallest(items);
+    MenuInstance::new(menu).unwrap()
+}
+
+fn make_discard_menu(meters: Vec<MeterType>) -> MenuInstance<BetweenLevelChoice> {
+    let mut items = meters
+        .iter()
+        .map(|&typ| (meter::meter_name(typ), BetweenLevelChoice::Discard(typ)))
+        .collect::<Vec<_>>();
+
+    items.push(("Back", BetweenLevelChoice::Back));
+
+    let menu = Menu::smallest(items);
+    MenuInstance::new(menu).unwrap()
+}
+
+fn make_swap_menu(discard: MeterType, upgrades: &[MeterType]) -> MenuInstance<BetweenLevelChoice> {
+    let mut items = upgrades
+        .iter()
+        .map(|&typ| {
+            (
+                format!(
+                    "Swap {} for {}",
+                    meter::meter_short_name(discard),
+                    meter::meter_short_name(typ)
+                ),
+                BetweenLevelChoice::Swap(discard, typ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    items.push(("Back".to_string(), BetweenLevelChoice::Back));
+
     let menu = Menu::smallest(items);
     MenuInstance::new(menu).unwrap()
 }
@@ -481,6 +683,16 @@ fn make_main_menu(in_progress: bool, frontend: Frontend) -> MenuInstance<MainMen
     MenuInstance::new(main_menu).unwrap()
 }
 
+fn stats_lines(stats: Stats) -> Vec<String> {
+    vec![
+        format!("Enemies killed by you: {}", stats.kills_by_player),
+        format!("Enemies killed by enemies: {}", stats.kills_by_npcs),
+        format!("Railgun hits: {}", stats.rail_gun_hits),
+        format!("Damage dealt: {}", stats.damage_dealt),
+        format!("Damage taken: {}", stats.damage_taken),
+    ]
+}
+
 fn alert_str(alert: Alert) -> (TextInfo, &'static str) {
     match alert {
         Alert::NoStamina => (
@@ -529,12 +741,72 @@ fn alert_str(alert: Alert) -> (TextInfo, &'static str) {
                 .foreground_colour(Rgb24::new(127, 63, 255)),
             "Select a direction to blink.",
         ),
+        Alert::GrenadeWhichDirection => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 127, 0)),
+            "Select a direction to throw.",
+        ),
+        Alert::DecoyWhichDirection => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(127, 127, 0)),
+            "Select a direction to place the decoy.",
+        ),
+        Alert::NoDecoy => (
+            TextInfo::default().bold().foreground_colour(colours::RED),
+            "Decoy is still recharging!",
+        ),
+        Alert::NoHaste => (
+            TextInfo::default().bold().foreground_colour(colours::RED),
+            "Haste is still recharging!",
+        ),
+        Alert::ShieldWhichDirection => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(0, 127, 255)),
+            "Select a direction to raise your shield.",
+        ),
+        Alert::NoShield => (
+            TextInfo::default().bold().foreground_colour(colours::RED),
+            "Shield is depleted!",
+        ),
+        Alert::ShieldBlock => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(0, 127, 255)),
+            "Your shield blocks the attack.",
+        ),
         Alert::BeaconActive => (
             TextInfo::default()
                 .bold()
                 .foreground_colour(Rgb24::new(255, 0, 0)),
             "EMERGENCY BEACON ACTIVE BEEP BEEP BEEP BEEP",
         ),
+        Alert::Crushed => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 255, 0)),
+            "Crunch! The enemy slams into something solid.",
+        ),
+        Alert::DamageResisted => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(127, 127, 127)),
+            "It shrugs off the attack.",
+        ),
+        Alert::DamageWeakness => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 255, 0)),
+            "It's especially vulnerable to that!",
+        ),
+        Alert::Explored => (
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(0, 255, 255)),
+            "There is nowhere left to explore.",
+        ),
     }
 }
 
@@ -544,16 +816,21 @@ impl<S: Storage> App<S> {
 
         let existing_state: Option<FullSaveState> = storage.load(SAVE_FILE).ok();
 
-        let (in_progress, state, between_level_menu, game_state) =
+        let (in_progress, state, between_level_menu, upgrade_choices, game_state) =
             if let Some(state) = existing_state {
                 (
                     true,
                     State::from(state.game),
                     state.between_level_menu,
+                    state.upgrade_choices,
                     state.game_state,
                 )
             } else {
-                (false, State::new(rng.gen()), None, GameState::Level)
+                let mut state = State::new(rng.gen());
+                if let Ok(pins) = storage.load(LOADOUT_FILE) {
+                    state.set_loadout_pins(pins);
+                }
+                (false, state, None, Vec::new(), GameState::Level)
             };
 
         let main_menu = make_main_menu(in_progress, frontend);
@@ -567,6 +844,7 @@ impl<S: Storage> App<S> {
         Self {
             main_menu,
             between_level_menu,
+            upgrade_choices,
             state,
             game_state,
             app_state,
@@ -578,14 +856,38 @@ impl<S: Storage> App<S> {
             frontend,
             save_remaining,
             alert: None,
+            loadout_cursor: 0,
+            examine_cursor: Coord::new(0, 0),
+            mouse_coord: None,
+            travel: None,
         }
     }
 
+    // the cell of the map under a mouse coordinate, if any
+    fn map_coord(&self, mouse_coord: Coord) -> Option<Coord> {
+        let coord = mouse_coord - Coord::new(0, GAME_TOP_PADDING);
+        if coord.is_valid(self.state.size()) {
+            Some(coord)
+        } else {
+            None
+        }
+    }
+
+    fn new_state(&mut self) -> State {
+        let mut state = State::new(self.rng.gen());
+        state.set_loadout_pins(self.state.loadout().pins().clone());
+        state
+    }
+
     pub fn store(&mut self) {
+        self.storage
+            .store(LOADOUT_FILE, self.state.loadout().pins())
+            .expect("Failed to save loadout");
         if self.in_progress {
             let state = FullSaveState {
                 game: self.state.save(self.rng.gen()),
                 between_level_menu: self.between_level_menu.clone(),
+                upgrade_choices: self.upgrade_choices.clone(),
                 game_state: self.game_state,
             };
             self.storage
@@ -640,7 +942,7 @@ impl<S: Storage> App<S> {
                                 None
                             }
                             MainMenuChoice::NewGame => {
-                                self.state = State::new(self.rng.gen());
+                                self.state = self.new_state();
 
                                 if self.frontend == Frontend::Glutin {
                                     println!("RNG Seed: {}", self.state.rng_seed());
@@ -670,6 +972,33 @@ impl<S: Storage> App<S> {
                 match self.game_state {
                     GameState::Level => {
                         for input in inputs {
+                            match input {
+                                ProtottyInput::MouseMove { coord, .. } => {
+                                    self.mouse_coord = self.map_coord(coord);
+                                    continue;
+                                }
+                                ProtottyInput::MousePress { button, coord } => {
+                                    if let Some(coord) = self.map_coord(coord) {
+                                        match button {
+                                            MouseButton::Left => {
+                                                self.travel = Some(Travel::new(coord, &self.state));
+                                            }
+                                            MouseButton::Right => {
+                                                self.travel = None;
+                                                self.examine_cursor = coord;
+                                                self.app_state = AppState::Examine;
+                                                break;
+                                            }
+                                            MouseButton::Middle => (),
+                                        }
+                                    }
+                                    continue;
+                                }
+                                ProtottyInput::MouseRelease { .. }
+                                | ProtottyInput::MouseScroll { .. } => continue,
+                                // pressing any key stops travelling
+                                _ => self.travel = None,
+                            }
                             let input_type = match input {
                                 ProtottyInput::Up => InputType::Game(MetersInput::Direction(North)),
                                 ProtottyInput::Down => {
@@ -686,6 +1015,20 @@ impl<S: Storage> App<S> {
                                     let identifier = ActiveMeterIdentifier::from_char(ch);
                                     InputType::Game(MetersInput::ActiveMeterSelect(identifier))
                                 }
+                                ProtottyInput::Char('l') => {
+                                    self.loadout_cursor = 0;
+                                    self.app_state = AppState::Loadout;
+                                    break;
+                                }
+                                ProtottyInput::Char('o') => {
+                                    self.travel = Some(Travel::explore(&self.state));
+                                    continue;
+                                }
+                                ProtottyInput::Char('x') => {
+                                    self.examine_cursor = self.state.player_coord();
+                                    self.app_state = AppState::Examine;
+                                    break;
+                                }
                                 prototty_inputs::ETX => InputType::ControlFlow(ControlFlow::Quit),
                                 prototty_inputs::ESCAPE => {
                                     if self.state.selected_meter_type().is_some() {
@@ -705,11 +1048,27 @@ impl<S: Storage> App<S> {
                             }
                         }
 
+                        if self.input_buffer.is_empty() && self.state.is_awaiting_input() {
+                            let travel_input = match self.travel {
+                                Some(ref mut travel) => travel.next_input(&mut self.state),
+                                None => None,
+                            };
+                            if let Some(input) = travel_input {
+                                self.input_buffer.push(input);
+                            } else {
+                                if self.travel.as_ref().is_some_and(Travel::is_explored) {
+                                    self.alert = Some(Alert::Explored);
+                                }
+                                self.travel = None;
+                            }
+                        }
+
                         if !self.input_buffer.is_empty() {
                             self.alert = None;
                         }
 
                         if let Some(meta) = self.state.tick(self.input_buffer.drain(..), period) {
+                            self.travel = None;
                             match meta {
                                 ExternalEvent::Lose => {
                                     self.app_state = AppState::GameOver(GameOverMessage::Lose);
@@ -725,14 +1084,16 @@ impl<S: Storage> App<S> {
                                         self.between_level_menu = Some(make_non_upgrade_menu());
                                     }
                                     AscendStatus::CompleteGoal => {
-                                        let choices = self.state.upgrade_choices();
-                                        if choices.is_empty() {
+                                        self.upgrade_choices = self.state.upgrade_choices();
+                                        if self.upgrade_choices.is_empty() {
                                             self.game_state = GameState::NonUpgradeMenu;
                                             self.between_level_menu = Some(make_non_upgrade_menu());
                                         } else {
                                             self.game_state = GameState::UpgradeMenu;
-                                            self.between_level_menu =
-                                                Some(make_upgrade_menu(choices));
+                                            self.between_level_menu = Some(make_upgrade_menu(
+                                                &self.upgrade_choices,
+                                                !self.state.discardable_meters().is_empty(),
+                                            ));
                                         }
                                     }
                                 },
@@ -742,25 +1103,50 @@ impl<S: Storage> App<S> {
                             }
                         }
                     }
-                    GameState::UpgradeMenu => {
-                        if let Some(menu) = self.between_level_menu.as_mut() {
-                            if let Some(output) = menu.tick_with_mouse(
+                    GameState::UpgradeMenu | GameState::DiscardMenu => {
+                        let output = if let Some(menu) = self.between_level_menu.as_mut() {
+                            menu.tick_with_mouse(
                                 inputs,
                                 &view.between_level_view.view.main_menu_view,
-                            ) {
-                                match output {
-                                    MenuOutput::Quit => return Some(ControlFlow::Quit),
-                                    MenuOutput::Cancel => {
-                                        self.app_state = AppState::MainMenu;
+                            )
+                        } else {
+                            None
+                        };
+                        if let Some(output) = output {
+                            match output {
+                                MenuOutput::Quit => return Some(ControlFlow::Quit),
+                                MenuOutput::Cancel => {
+                                    self.app_state = AppState::MainMenu;
+                                }
+                                MenuOutput::Finalise(selection) => match selection {
+                                    BetweenLevelChoice::Upgrade(upgrade) => {
+                                        self.state.switch_levels_upgrade(upgrade);
+                                        self.app_state = AppState::Game;
+                                        self.game_state = GameState::Level;
                                     }
-                                    MenuOutput::Finalise(selection) => {
-                                        if let BetweenLevelChoice::Upgrade(upgrade) = selection {
-                                            self.state.switch_levels_upgrade(upgrade);
-                                            self.app_state = AppState::Game;
-                                            self.game_state = GameState::Level;
-                                        }
+                                    BetweenLevelChoice::Swap(discard, upgrade) => {
+                                        self.state.switch_levels_swap(discard, upgrade);
+                                        self.app_state = AppState::Game;
+                                        self.game_state = GameState::Level;
                                     }
-                                }
+                                    BetweenLevelChoice::ChooseDiscard => {
+                                        self.game_state = GameState::DiscardMenu;
+                                        self.between_level_menu = Some(make_discard_menu(
+                                            self.state.discardable_meters(),
+                                        ));
+                                    }
+                                    BetweenLevelChoice::Discard(discard) => {
+                                        self.game_state = GameState::UpgradeMenu;
+                                        self.between_level_menu =
+                                            Some(make_swap_menu(discard, &self.upgrade_choices));
+                                    }
+                                    BetweenLevelChoice::Back => {
+                                        self.game_state = GameState::UpgradeMenu;
+                                        self.between_level_menu =
+                                            Some(make_upgrade_menu(&self.upgrade_choices, true));
+                                    }
+                                    BetweenLevelChoice::Continue => (),
+                                },
                             }
                         }
                     }
@@ -784,6 +1170,67 @@ impl<S: Storage> App<S> {
                 }
                 None
             }
+            AppState::Loadout => {
+                for input in inputs {
+                    match input {
+                        ProtottyInput::Up => {
+                            self.loadout_cursor = self.loadout_cursor.saturating_sub(1);
+                        }
+                        ProtottyInput::Down => {
+                            self.loadout_cursor =
+                                ::std::cmp::min(self.loadout_cursor + 1, NUM_SLOTS - 1);
+                        }
+                        ProtottyInput::Char(ch @ '0'..='9') => {
+                            let identifier = ActiveMeterIdentifier::from_char(ch);
+                            self.state.swap_loadout_slots(
+                                ActiveMeterIdentifier::from_index(self.loadout_cursor),
+                                identifier,
+                            );
+                            self.loadout_cursor = identifier.to_index();
+                        }
+                        ProtottyInput::Char('p') => {
+                            let identifier = ActiveMeterIdentifier::from_index(self.loadout_cursor);
+                            self.state.toggle_loadout_pin(identifier);
+                        }
+                        ProtottyInput::Char('l') | prototty_inputs::ESCAPE => {
+                            self.app_state = AppState::Game;
+                            break;
+                        }
+                        prototty_inputs::ETX => return Some(ControlFlow::Quit),
+                        _ => (),
+                    }
+                }
+                None
+            }
+            AppState::Examine => {
+                for input in inputs {
+                    let direction = match input {
+                        ProtottyInput::MouseMove { coord, .. }
+                        | ProtottyInput::MousePress { coord, .. } => {
+                            if let Some(coord) = self.map_coord(coord) {
+                                self.examine_cursor = coord;
+                            }
+                            continue;
+                        }
+                        ProtottyInput::Up => North,
+                        ProtottyInput::Down => South,
+                        ProtottyInput::Left => West,
+                        ProtottyInput::Right => East,
+                        ProtottyInput::Char('x') | prototty_inputs::ESCAPE => {
+                            self.app_state = AppState::Game;
+                            break;
+                        }
+                        prototty_inputs::ETX => return Some(ControlFlow::Quit),
+                        _ => continue,
+                    };
+                    let size = self.state.size();
+                    let cursor = self.examine_cursor + direction.coord();
+                    if cursor.is_valid(size) {
+                        self.examine_cursor = cursor;
+                    }
+                }
+                None
+            }
             AppState::GameOver(_) => {
                 if let Some(remaining) = self.game_over_duration.checked_sub(period) {
                     self.game_over_duration = remaining;
@@ -791,7 +1238,7 @@ impl<S: Storage> App<S> {
                     self.in_progress = false;
                     self.main_menu = make_main_menu(false, self.frontend);
                     self.app_state = AppState::MainMenu;
-                    self.state = State::new(self.rng.gen());
+                    self.state = self.new_state();
                 }
                 None
             }
@@ -0,0 +1,82 @@
+use meters::loadout::*;
+use prototty::*;
+use std::fmt::Write;
+
+use super::meter::*;
+
+const TITLE: &str = "Loadout";
+const HELP: &str = "Select:↑↓  Move to slot:0-9  Pin:P  Back:ESC";
+const SLOTS_TOP_Y: i32 = 2;
+const NAME_PADDING: usize = 9;
+
+pub struct LoadoutView {
+    scratch: String,
+}
+
+impl LoadoutView {
+    pub fn new() -> Self {
+        Self {
+            scratch: String::new(),
+        }
+    }
+}
+
+impl<'a> View<(&'a Loadout, usize)> for LoadoutView {
+    fn view<G: ViewGrid>(
+        &mut self,
+        &(loadout, cursor): &(&'a Loadout, usize),
+        offset: Coord,
+        depth: i32,
+        grid: &mut G,
+    ) {
+        TextInfoStringView.view(
+            &(TextInfo::default().bold().underline(), TITLE),
+            offset,
+            depth,
+            grid,
+        );
+        for (y, slot) in loadout.slots().enumerate() {
+            self.scratch.clear();
+            let selector = if y == cursor { ">" } else { " " };
+            write!(self.scratch, "{} {}) ", selector, slot.identifier.to_char()).unwrap();
+            let info = if let Some(typ) = slot.typ {
+                write!(
+                    self.scratch,
+                    "{:1$}",
+                    meter_short_name(typ.typ()),
+                    NAME_PADDING
+                )
+                .unwrap();
+                if slot.is_pinned {
+                    write!(self.scratch, " (pinned)").unwrap();
+                }
+                meter_text_info(typ.typ())
+            } else {
+                write!(self.scratch, "-").unwrap();
+                TextInfo::default()
+            };
+            let info = if y == cursor { info.bold() } else { info };
+            TextInfoStringView.view(
+                &(info, &self.scratch),
+                offset + Coord::new(0, SLOTS_TOP_Y + y as i32),
+                depth,
+                grid,
+            );
+        }
+        StringView.view(
+            HELP,
+            offset + Coord::new(0, SLOTS_TOP_Y + NUM_SLOTS as i32 + 1),
+            depth,
+            grid,
+        );
+    }
+}
+
+impl<'a> ViewSize<(&'a Loadout, usize)> for LoadoutView {
+    fn size(&mut self, _: &(&'a Loadout, usize)) -> Size {
+        Size::new(
+            HELP.chars().count() as u32,
+            SLOTS_TOP_Y as u32 + NUM_SLOTS as u32 + 2,
+        )
+    }
+}
@@ -1,9 +1,10 @@
 use meters::goal::*;
 use meters::meter::*;
+use meters::status::*;
 use prototty::*;
 use std::fmt::Write;
 
-fn meter_text_info(typ: MeterType) -> TextInfo {
+pub fn meter_text_info(typ: MeterType) -> TextInfo {
     let colour = match typ {
         MeterType::Gun => Rgb24::new(150, 200, 50),
         MeterType::Medkit => colours::GREEN,
@@ -15,6 +16,13 @@ fn meter_text_info(typ: MeterType) -> TextInfo {
         MeterType::Blink => Rgb24::new(127, 63, 255),
         MeterType::Compass => Rgb24::new(255, 63, 255),
         MeterType::Push => Rgb24::new(255, 255, 0),
+        MeterType::Grenade => Rgb24::new(255, 127, 0),
+        MeterType::Flare => Rgb24::new(255, 0, 63),
+        MeterType::Decoy => Rgb24::new(127, 127, 0),
+        MeterType::Shield => Rgb24::new(0, 127, 255),
+        MeterType::Haste => Rgb24::new(255, 255, 127),
+        MeterType::Regeneration => Rgb24::new(0, 255, 127),
+        MeterType::Stealth => Rgb24::new(127, 127, 127),
     };
     TextInfo {
         foreground_colour: Some(colour),
@@ -34,6 +42,46 @@ fn goal_meter_text_info(typ: GoalMeterType) -> TextInfo {
     }
 }
 
+pub fn status_effect_text_info(typ: StatusEffectType) -> TextInfo {
+    let colour = match typ {
+        StatusEffectType::TransformDelayed => Rgb24::new(127, 0, 0),
+        StatusEffectType::Pushed => Rgb24::new(255, 255, 0),
+        StatusEffectType::Poisoned => Rgb24::new(0, 191, 0),
+        StatusEffectType::Slowed => Rgb24::new(0, 127, 255),
+        StatusEffectType::Stunned => Rgb24::new(255, 255, 127),
+        StatusEffectType::Burning => Rgb24::new(255, 127, 0),
+        StatusEffectType::Hasted => Rgb24::new(255, 255, 127),
+    };
+    TextInfo {
+        foreground_colour: Some(colour),
+        ..Default::default()
+    }
+}
+
+pub fn status_effect_name(typ: StatusEffectType) -> &'static str {
+    match typ {
+        StatusEffectType::TransformDelayed => "transform delayed",
+        StatusEffectType::Pushed => "pushed",
+        StatusEffectType::Poisoned => "poisoned",
+        StatusEffectType::Slowed => "slowed",
+        StatusEffectType::Stunned => "stunned",
+        StatusEffectType::Burning => "burning",
+        StatusEffectType::Hasted => "hasted",
+    }
+}
+
+pub fn status_effect_short_name(typ: StatusEffectType) -> &'static str {
+    match typ {
+        StatusEffectType::TransformDelayed => "Delayed",
+        StatusEffectType::Pushed => "Pushed",
+        StatusEffectType::Poisoned => "Poisoned",
+        StatusEffectType::Slowed => "Slowed",
+        StatusEffectType::Stunned => "Stunned",
+        StatusEffectType::Burning => "Burning",
+        StatusEffectType::Hasted => "Hasted",
+    }
+}
+
 pub struct MeterView {
     name_padding: usize,
     meter_width: usize,
@@ -46,6 +94,13 @@ pub fn meter_name(typ: MeterType) -> &'static str {
         MeterType::Medkit => "Medkit - use to heal, recharges over time",
         MeterType::Metabol => "Metabol - delay transformations of nearby enemies",
         MeterType::Push => "Push - push nearby enemies away from you",
+        MeterType::Grenade => "Grenade - throw an explosive with a short fuse",
+        MeterType::Flare => "Flare - light up everything nearby for a few turns",
+        MeterType::Decoy => "Decoy - place a target to lure enemies away from you",
+        MeterType::Shield => "Shield - block attacks from one direction",
+        MeterType::Haste => "Haste - act twice as often for a few turns",
+        MeterType::Regeneration => "Regeneration - slowly heal when not in melee",
+        MeterType::Stealth => "Stealth - chance to go unnoticed by sleeping enemies",
         MeterType::Health => "Health - be alive",
         MeterType::Stamina => "Stamina - spend to punch, recharges over time",
         MeterType::Kevlar => "Armour - chance to negate damage",
@@ -55,6 +110,28 @@ pub fn meter_name(typ: MeterType) -> &'static str {
     }
 }
 
+pub fn meter_short_name(typ: MeterType) -> &'static str {
+    match typ {
+        MeterType::Gun => "Quadgun",
+        MeterType::Medkit => "Medkit",
+        MeterType::Metabol => "Metabol",
+        MeterType::Push => "Push",
+        MeterType::RailGun => "Railgun",
+        MeterType::Blink => "Blink",
+        MeterType::Grenade => "Grenade",
+        MeterType::Flare => "Flare",
+        MeterType::Decoy => "Decoy",
+        MeterType::Shield => "Shield",
+        MeterType::Haste => "Haste",
+        MeterType::Regeneration => "Regen",
+        MeterType::Stealth => "Stealth",
+        MeterType::Health => "Health",
+        MeterType::Stamina => "Stamina",
+        MeterType::Kevlar => "Armour",
+        MeterType::Compass => "Compass",
+    }
+}
+
 impl MeterView {
     pub fn new(name_padding: usize, meter_width: usize) -> Self {
         Self {
@@ -67,43 +144,33 @@ impl MeterView {
         let seperator = if is_selected { "*" } else { " " };
 
         write!(self.scratch, "{}){}", identifier, seperator).unwrap();
-        match typ {
-            ActiveMeterType::Gun => {
-                write!(self.scratch, "{:1$}", "Quadgun", self.name_padding).unwrap()
-            }
-            ActiveMeterType::Medkit => {
-                write!(self.scratch, "{:1$}", "Medkit", self.name_padding).unwrap()
-            }
-            ActiveMeterType::Metabol => {
-                write!(self.scratch, "{:1$}", "Metabol", self.name_padding).unwrap()
-            }
-            ActiveMeterType::Push => {
-                write!(self.scratch, "{:1$}", "Push", self.name_padding).unwrap()
-            }
-            ActiveMeterType::RailGun => {
-                write!(self.scratch, "{:1$}", "Railgun", self.name_padding).unwrap()
-            }
-            ActiveMeterType::Blink => {
-                write!(self.scratch, "{:1$}", "Blink", self.name_padding).unwrap()
-            }
-        }
+        write!(
+            self.scratch,
+            "{:1$}",
+            meter_short_name(typ.typ()),
+            self.name_padding
+        )
+        .unwrap();
     }
     fn write_passive_name(&mut self, typ: PassiveMeterType) {
         write!(self.scratch, "   ").unwrap();
-        match typ {
-            PassiveMeterType::Health => {
-                write!(self.scratch, "{:1$}", "Health", self.name_padding).unwrap()
-            }
-            PassiveMeterType::Stamina => {
-                write!(self.scratch, "{:1$}", "Stamina", self.name_padding).unwrap()
-            }
-            PassiveMeterType::Kevlar => {
-                write!(self.scratch, "{:1$}", "Armour", self.name_padding).unwrap()
-            }
-            PassiveMeterType::Compass => {
-                write!(self.scratch, "{:1$}", "Compass", self.name_padding).unwrap()
-            }
-        }
+        write!(
+            self.scratch,
+            "{:1$}",
+            meter_short_name(typ.typ()),
+            self.name_padding
+        )
+        .unwrap();
+    }
+    fn write_status_effect_name(&mut self, typ: StatusEffectType) {
+        write!(self.scratch, "   ").unwrap();
+        write!(
+            self.scratch,
+            "{:1$}",
+            status_effect_short_name(typ),
+            self.name_padding
+        )
+        .unwrap();
     }
     fn write_goal_name(&mut self, typ: GoalMeterType) {
         write!(self.scratch, "   ").unwrap();
@@ -120,6 +187,9 @@ impl MeterView {
         }
     }
     fn write_meter(&mut self, meter: Meter) {
+        self.write_meter_with_boundaries(meter, &[]);
+    }
+    fn write_meter_with_boundaries(&mut self, meter: Meter, boundaries: &[i32]) {
         let value = ::std::cmp::max(meter.value, 0) as usize;
         let max = ::std::cmp::max(meter.max, 0) as usize;
         let filled_meter_width = (self.meter_width * value) / max;
@@ -129,11 +199,17 @@ impl MeterView {
             filled_meter_width
         };
         let remaining_meter_width = self.meter_width.saturating_sub(filled_meter_width);
-        for _ in 0..filled_meter_width {
-            self.scratch.push('█');
-        }
-        for _ in 0..remaining_meter_width {
-            self.scratch.push('░')
+        for i in 0..(filled_meter_width + remaining_meter_width) {
+            let is_boundary = boundaries
+                .iter()
+                .any(|&boundary| boundary > 0 && (self.meter_width * boundary as usize) / max == i);
+            if is_boundary {
+                self.scratch.push('│');
+            } else if i < filled_meter_width {
+                self.scratch.push('█');
+            } else {
+                self.scratch.push('░');
+            }
         }
 
         write!(self.scratch, " {}/{}", value, max).unwrap();
@@ -172,11 +248,27 @@ impl View<PassiveMeterInfo> for MeterView {
     }
 }
 
+impl View<(StatusEffectType, Meter)> for MeterView {
+    fn view<G: ViewGrid>(
+        &mut self,
+        &(typ, meter): &(StatusEffectType, Meter),
+        offset: Coord,
+        depth: i32,
+        grid: &mut G,
+    ) {
+        self.scratch.clear();
+        self.write_status_effect_name(typ);
+        self.write_meter(meter);
+        let info = status_effect_text_info(typ);
+        TextInfoStringView.view(&(info, &self.scratch), offset, depth, grid);
+    }
+}
+
 impl View<GoalMeterInfo> for MeterView {
     fn view<G: ViewGrid>(&mut self, info: &GoalMeterInfo, offset: Coord, depth: i32, grid: &mut G) {
         self.scratch.clear();
         self.write_goal_name(info.typ);
-        self.write_meter(info.meter);
+        self.write_meter_with_boundaries(info.meter, info.boundaries);
         let info = goal_meter_text_info(info.typ);
         TextInfoStringView.view(&(info, &self.scratch), offset, depth, grid);
     }
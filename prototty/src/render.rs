@@ -1,4 +1,5 @@
 use direction::CardinalDirection;
+use meters::status::StatusEffectType;
 use meters::tile::Tile;
 use meters::tile_info::TileInfo;
 use prototty::*;
@@ -6,15 +7,23 @@ use prototty::*;
 pub fn render_when_non_visible(tile: Tile) -> bool {
     match tile {
         Tile::Player
+        | Tile::Decoy
+        | Tile::Shield(_)
         | Tile::Punch(_)
         | Tile::Egg
+        | Tile::VolatileEgg
         | Tile::Larvae
         | Tile::Chrysalis
         | Tile::Aracnoid
         | Tile::Beetoid
+        | Tile::Spitter
         | Tile::SuperEgg
         | Tile::Queen
         | Tile::Bullet
+        | Tile::Spit
+        | Tile::Grenade
+        | Tile::Explosion
+        | Tile::Flare
         | Tile::MetabolWave
         | Tile::PushWave
         | Tile::RailGunShotHorizontal
@@ -30,6 +39,8 @@ pub fn render_when_non_visible(tile: Tile) -> bool {
         | Tile::KevlarPickup
         | Tile::MetabolAmmoPickup
         | Tile::PushAmmoPickup
+        | Tile::GrenadeAmmoPickup
+        | Tile::FlareAmmoPickup
         | Tile::BeaconActive
         | Tile::BeaconInactive
         | Tile::RailGunAmmoPickup => true,
@@ -52,6 +63,12 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
                 .bold()
                 .foreground_colour(Rgb24::new(255, 255, 0)),
         ),
+        Tile::Decoy => (
+            '@',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(127, 127, 0)),
+        ),
         Tile::Wall => (
             '#',
             TextInfo::default()
@@ -90,12 +107,30 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
                     .foreground_colour(Rgb24::new(255, 0, 0)),
             )
         }
+        Tile::Shield(direction) => {
+            let ch = match direction {
+                CardinalDirection::North | CardinalDirection::South => '─',
+                CardinalDirection::East | CardinalDirection::West => '│',
+            };
+            (
+                ch,
+                TextInfo::default()
+                    .bold()
+                    .foreground_colour(Rgb24::new(0, 127, 255)),
+            )
+        }
         Tile::Egg => (
             'ê',
             TextInfo::default()
                 .bold()
                 .foreground_colour(Rgb24::new(0, 255, 0)),
         ),
+        Tile::VolatileEgg => (
+            'ê',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 127, 0)),
+        ),
         Tile::Larvae => (
             'l',
             TextInfo::default()
@@ -120,6 +155,12 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
                 .bold()
                 .foreground_colour(Rgb24::new(255, 255, 127)),
         ),
+        Tile::Spitter => (
+            's',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(127, 255, 63)),
+        ),
         Tile::SuperEgg => (
             'Ē',
             TextInfo::default()
@@ -150,6 +191,32 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
                 .bold()
                 .foreground_colour(Rgb24::new(150, 200, 50)),
         ),
+        Tile::Spit => (
+            '∙',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(63, 255, 0)),
+        ),
+        Tile::Grenade => (
+            'ó',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 127, 0)),
+        ),
+        Tile::Explosion => (
+            '*',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 255, 0))
+                .background_colour(Rgb24::new(255, 63, 0)),
+        ),
+        Tile::Flare => (
+            '¡',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 255, 255))
+                .background_colour(Rgb24::new(255, 0, 63)),
+        ),
         Tile::HealthPickup => (
             '♥',
             TextInfo::default()
@@ -174,6 +241,18 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
                 .bold()
                 .foreground_colour(Rgb24::new(255, 255, 0)),
         ),
+        Tile::GrenadeAmmoPickup => (
+            'Ó',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 127, 0)),
+        ),
+        Tile::FlareAmmoPickup => (
+            '¡',
+            TextInfo::default()
+                .bold()
+                .foreground_colour(Rgb24::new(255, 0, 63)),
+        ),
         Tile::RailGunShotHorizontal => (
             '═',
             TextInfo::default()
@@ -216,7 +295,10 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
         text_info.foreground_colour = Some(Rgb24::new(255, 0, 0));
     } else if let Some(health_meter) = tile_info.health_meter {
         if health_meter.value == 1 && health_meter.max > 1 {
-            if tile_info.delayed_transform {
+            if tile_info
+                .status_effects
+                .contains(StatusEffectType::TransformDelayed)
+            {
                 text_info.foreground_colour = Some(Rgb24::new(0, 0, 0));
             } else {
                 text_info.foreground_colour = Some(Rgb24::new(127, 0, 0));
@@ -234,17 +316,22 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
         }
     }
 
-    if tile_info.delayed_transform {
-        match tile_info.tile {
-            Tile::SuperEgg | Tile::Chrysalis | Tile::Egg | Tile::Larvae => {
-                text_info.background_colour = Some(Rgb24::new(127, 0, 0));
-            }
-            _ => (),
-        }
-    }
-
-    if tile_info.pushed {
-        text_info.background_colour = Some(Rgb24::new(255, 255, 0));
+    for typ in tile_info.status_effects.iter() {
+        let background_colour = match typ {
+            StatusEffectType::TransformDelayed => match tile_info.tile {
+                Tile::SuperEgg | Tile::Chrysalis | Tile::Egg | Tile::VolatileEgg | Tile::Larvae => {
+                    Rgb24::new(127, 0, 0)
+                }
+                _ => continue,
+            },
+            StatusEffectType::Pushed => Rgb24::new(255, 255, 0),
+            StatusEffectType::Poisoned => Rgb24::new(0, 63, 0),
+            StatusEffectType::Slowed => Rgb24::new(0, 31, 95),
+            StatusEffectType::Stunned => Rgb24::new(95, 95, 31),
+            StatusEffectType::Burning => Rgb24::new(127, 31, 0),
+            StatusEffectType::Hasted => Rgb24::new(95, 95, 0),
+        };
+        text_info.background_colour = Some(background_colour);
     }
 
     if let Some(1) = tile_info.countdown {
@@ -253,6 +340,9 @@ pub fn tile_text(tile_info: TileInfo) -> (char, TextInfo) {
             Tile::Egg => {
                 text_info.background_colour = Some(Rgb24::new(0, x, 0));
             }
+            Tile::VolatileEgg => {
+                text_info.background_colour = Some(Rgb24::new(x, x / 2, 0));
+            }
             Tile::Chrysalis => {
                 text_info.background_colour = Some(Rgb24::new(0, x, x));
             }